@@ -0,0 +1,228 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    CellState, CustomRule, Cyclic, Grid, LargerThanLife, Margolus, Rule, RuleError, Wolfram,
+};
+
+/// Rule of any of the supported families, written in its usual notation.
+///
+/// Strings starting with `R` and a number are [`LargerThanLife`] rules, or
+/// [`Cyclic`] rules if their parts are separated by `/`, strings starting with
+/// `W` or `T` and a number are one-dimensional [`Wolfram`] rules, strings
+/// starting with `MS,D` and the names `Critters`, `Tron` and `BBM` are
+/// [`Margolus`] block rules, `WireWorld`, `Static` and `Lenia` (in any case)
+/// are [`Automaton::Wireworld`], [`Automaton::Static`] and [`Automaton::Lenia`]
+/// and all others are parsed as a Life-like [`Rule`]. `Immigration` and
+/// `QuadLife` are the Life-like rules `B3/S23/K2` and `B3/S23/K4`. A Life-like
+/// rule ending in `/Rev` like `B3/S23/Rev` is run as a second-order rule, see
+/// [`Automaton::SecondOrder`].
+///
+/// [`Automaton::Custom`] rules can't be parsed, they are looked up by name in
+/// a [`crate::RuleRegistry`] instead. They are written as their name, so
+/// they can't be read back.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Automaton {
+    Life(Rule),
+    /// Life-like rule that also flips the cells alive in the previous
+    /// generation so it can run backwards, see [`crate::SecondOrderBoard`].
+    SecondOrder(Rule),
+    LargerThanLife(LargerThanLife),
+    /// Cells cycling through their states, see [`crate::CyclicBoard`].
+    Cyclic(Cyclic),
+    /// One-dimensional rule shown as a space-time diagram, see
+    /// [`crate::WolframBoard`].
+    Wolfram(Wolfram),
+    /// Block rule replacing 2x2 blocks at once, see
+    /// [`crate::MargolusBoard`].
+    Margolus(Margolus),
+    /// Electrons moving along wires, see [`crate::WireworldBoard`].
+    Wireworld,
+    /// Cells that only change when they are edited or written by turmites,
+    /// see [`crate::StaticBoard`]. Written `Static/C<states>` for more than
+    /// 2 states like Generations rules, e.g. for turmites with more colors.
+    Static(u8),
+    /// Continuous cells with a level between 0 and 1, see
+    /// [`crate::LeniaBoard`]. The parameters of the rule are part of the
+    /// board.
+    Lenia,
+    /// Rule implemented outside of this crate, see [`crate::CellRule`].
+    Custom(CustomRule),
+}
+
+impl Automaton {
+    /// Number of states including alive and dead.
+    pub fn states(&self) -> u8 {
+        match self {
+            Automaton::Life(rule) => rule.states(),
+            Automaton::SecondOrder(_) => 2,
+            Automaton::LargerThanLife(rule) => rule.states(),
+            Automaton::Cyclic(rule) => rule.states(),
+            Automaton::Wolfram(rule) => rule.colors(),
+            Automaton::Margolus(_) => 2,
+            Automaton::Wireworld => 4,
+            Automaton::Static(states) => *states,
+            Automaton::Lenia => 2,
+            Automaton::Custom(rule) => rule.rule().states(),
+        }
+    }
+
+    /// Number of colors of alive cells, `1` unless this is a multi-color
+    /// rule.
+    pub fn colors(&self) -> u8 {
+        match self {
+            Automaton::Life(rule) => rule.colors(),
+            Automaton::Cyclic(rule) => rule.states(),
+            Automaton::Custom(rule) => rule.colors(),
+            _ => 1,
+        }
+    }
+
+    /// Grid the cells are laid out on.
+    pub fn grid(&self) -> Grid {
+        match self {
+            Automaton::Life(rule) | Automaton::SecondOrder(rule) => rule.neighborhood().grid(),
+            Automaton::LargerThanLife(_)
+            | Automaton::Cyclic(_)
+            | Automaton::Wolfram(_)
+            | Automaton::Margolus(_)
+            | Automaton::Wireworld
+            | Automaton::Static(_)
+            | Automaton::Lenia => Grid::Square,
+            Automaton::Custom(rule) => rule.rule().neighborhood().grid(),
+        }
+    }
+
+    /// Whether cells have a level between 0 and 1 instead of a state, see
+    /// [`crate::Universe::level`].
+    pub fn is_continuous(&self) -> bool {
+        matches!(self, Automaton::Lenia)
+    }
+
+    /// Converts from the state numbering used by Golly, see
+    /// [`CellState::from`]. Wireworld numbers empty cells `0`, electron heads
+    /// `1`, tails `2` and conductors `3`. Multi-color rules number alive
+    /// cells from `1` for the first color, cyclic rules their states from `1`
+    /// for the first state and custom rules use their own states.
+    pub fn cell_state(&self, state: u8) -> CellState {
+        match (self, state) {
+            (Automaton::Wireworld, 0) => CellState::Dead,
+            (Automaton::Wireworld, 1) => CellState::ElectronHead,
+            (Automaton::Wireworld, 2) => CellState::ElectronTail,
+            (Automaton::Wireworld, _) => CellState::Conductor,
+            (Automaton::Life(rule), color) if rule.colors() > 1 && color > 0 => {
+                CellState::Alive(color.min(rule.colors()) - 1)
+            }
+            (Automaton::Cyclic(rule), state) if state > 0 => {
+                CellState::Alive(state.min(rule.states()) - 1)
+            }
+            (Automaton::Custom(rule), state) => rule.cell_state(state),
+            (_, state) => CellState::from(state),
+        }
+    }
+}
+
+impl Default for Automaton {
+    fn default() -> Self {
+        Automaton::Life(Rule::default())
+    }
+}
+
+impl FromStr for Automaton {
+    type Err = RuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_ascii_lowercase();
+        if lower == "wireworld" {
+            return Ok(Automaton::Wireworld);
+        }
+        if lower == "lenia" {
+            return Ok(Automaton::Lenia);
+        }
+        if lower == "immigration" {
+            return Rule::conway().with_colors(2).map(Automaton::Life);
+        }
+        if lower == "quadlife" {
+            return Rule::conway().with_colors(4).map(Automaton::Life);
+        }
+        if let Some(rule) = lower.strip_suffix("/rev") {
+            return s.trim()[..rule.len()].parse().map(Automaton::SecondOrder);
+        }
+        if lower.starts_with("ms,d") || ["critters", "tron", "bbm"].contains(&lower.as_str()) {
+            return s.parse().map(Automaton::Margolus);
+        }
+        if let Some(states) = lower.strip_prefix("static") {
+            let states = match states.strip_prefix("/c") {
+                _ if states.is_empty() => return Ok(Automaton::Static(2)),
+                Some(states) => states,
+                None => return Err(RuleError::Malformed(s.to_string())),
+            };
+            if let Some(c) = states.chars().find(|c| !c.is_ascii_digit()) {
+                return Err(RuleError::UnexpectedChar(c));
+            }
+            let n: u32 = states
+                .parse()
+                .map_err(|_| RuleError::Malformed(s.to_string()))?;
+            return match u8::try_from(n) {
+                Ok(states) if states >= 2 => Ok(Automaton::Static(states)),
+                _ => Err(RuleError::StatesOutOfRange(n)),
+            };
+        }
+        let mut chars = s.trim().chars();
+        match (chars.next(), chars.next()) {
+            (Some('R'), Some(c)) | (Some('r'), Some(c)) if c.is_ascii_digit() => {
+                if s.contains('/') {
+                    s.parse().map(Automaton::Cyclic)
+                } else {
+                    s.parse().map(Automaton::LargerThanLife)
+                }
+            }
+            (Some(w), Some(c)) if "WwTt".contains(w) && c.is_ascii_digit() => {
+                s.parse().map(Automaton::Wolfram)
+            }
+            _ => s.parse().map(Automaton::Life),
+        }
+    }
+}
+
+impl fmt::Display for Automaton {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Automaton::Life(rule) if *rule == Rule::conway().with_colors(2).unwrap() => {
+                write!(f, "Immigration")
+            }
+            Automaton::Life(rule) if *rule == Rule::conway().with_colors(4).unwrap() => {
+                write!(f, "QuadLife")
+            }
+            Automaton::Life(rule) => rule.fmt(f),
+            Automaton::SecondOrder(rule) => write!(f, "{}/Rev", rule),
+            Automaton::LargerThanLife(rule) => rule.fmt(f),
+            Automaton::Cyclic(rule) => rule.fmt(f),
+            Automaton::Wolfram(rule) => rule.fmt(f),
+            Automaton::Margolus(rule) => rule.fmt(f),
+            Automaton::Wireworld => write!(f, "WireWorld"),
+            Automaton::Static(2) => write!(f, "Static"),
+            Automaton::Static(states) => write!(f, "Static/C{}", states),
+            Automaton::Lenia => write!(f, "Lenia"),
+            Automaton::Custom(rule) => rule.fmt(f),
+        }
+    }
+}
+
+impl TryFrom<String> for Automaton {
+    type Error = RuleError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Automaton> for String {
+    fn from(automaton: Automaton) -> Self {
+        automaton.to_string()
+    }
+}
@@ -0,0 +1,67 @@
+//! Word-parallel evaluation of two-state rules, 64 cells at a time.
+
+use crate::Rule;
+
+/// Two-state [`Rule`] applied to words of cells at once.
+#[derive(Debug, Clone)]
+pub(crate) struct BitRule {
+    birth: Vec<usize>,
+    survival: Vec<usize>,
+}
+
+impl BitRule {
+    pub(crate) fn new(rule: &Rule) -> Self {
+        Self {
+            birth: (0..=8).filter(|&n| rule.is_birth(n)).collect(),
+            survival: (0..=8).filter(|&n| rule.is_survival(n)).collect(),
+        }
+    }
+
+    /// Next generation of the cells in `alive`, where the n-th bit of every
+    /// word in `neighbors` is a neighbor of the n-th cell.
+    pub(crate) fn next(&self, alive: u64, neighbors: [u64; 8]) -> u64 {
+        let planes = count(neighbors);
+        let born = self
+            .birth
+            .iter()
+            .fold(0, |acc, &n| acc | equals(&planes, n));
+        let survives = self
+            .survival
+            .iter()
+            .fold(0, |acc, &n| acc | equals(&planes, n));
+        (alive & survives) | (!alive & born)
+    }
+}
+
+fn half_add(a: u64, b: u64) -> (u64, u64) {
+    (a ^ b, a & b)
+}
+
+fn full_add(a: u64, b: u64, c: u64) -> (u64, u64) {
+    let (sum, carry) = half_add(a, b);
+    let (sum, carry2) = half_add(sum, c);
+    (sum, carry | carry2)
+}
+
+/// Bit planes (1s, 2s, 4s and 8s) of the sum of 8 words per bit.
+fn count(n: [u64; 8]) -> [u64; 4] {
+    let (a, a2) = full_add(n[0], n[1], n[2]);
+    let (b, b2) = full_add(n[3], n[4], n[5]);
+    let (c, c2) = half_add(n[6], n[7]);
+    let (ones, d2) = full_add(a, b, c);
+    let (e, e4) = full_add(a2, b2, c2);
+    let (twos, f4) = half_add(e, d2);
+    let (fours, eights) = half_add(e4, f4);
+    [ones, twos, fours, eights]
+}
+
+/// Bits where the count given as bit planes is equal to `n`.
+fn equals(planes: &[u64; 4], n: usize) -> u64 {
+    planes.iter().enumerate().fold(!0, |acc, (i, plane)| {
+        if n & (1 << i) != 0 {
+            acc & plane
+        } else {
+            acc & !plane
+        }
+    })
+}
@@ -0,0 +1,177 @@
+use std::mem;
+
+use crate::stochastic::Random;
+use crate::{Bounds, CellState, Neighborhood, Rule, RuleError, Stochastic, Topology, Universe};
+
+/// Dense finite board storing every cell inside its bounds.
+///
+/// How neighbors across the edges are found depends on the [`Topology`].
+/// With the default [`Topology::Bounded`] cells outside of the bounds count as
+/// dead, so patterns die at the edges.
+///
+/// Hexagonal rules on a board that wraps around vertically need an even
+/// height, and triangular rules need an even width and height across edges
+/// that wrap around, otherwise the cells at the seam don't fit together.
+///
+/// The rule can be made random with [`Board::with_stochastic`].
+#[derive(Debug, Clone)]
+pub struct Board {
+    bounds: Bounds,
+    rule: Rule,
+    topology: Topology,
+    cells: Vec<CellState>,
+    // double buffer so stepping doesn't allocate
+    next: Vec<CellState>,
+    random: Option<Random>,
+    generation: u64,
+}
+
+impl Board {
+    pub fn new(bounds: Bounds, rule: Rule) -> Self {
+        let len = bounds.width() * bounds.height();
+        Self {
+            bounds,
+            rule,
+            topology: Topology::default(),
+            cells: vec![CellState::Dead; len],
+            next: vec![CellState::Dead; len],
+            random: None,
+            generation: 0,
+        }
+    }
+
+    pub fn with_topology(self, topology: Topology) -> Self {
+        Self { topology, ..self }
+    }
+
+    /// Applies the given probabilities to the rule, drawing random numbers
+    /// from `seed` so the same seed always gives the same generations.
+    pub fn with_stochastic(self, stochastic: Stochastic, seed: u64) -> Result<Self, RuleError> {
+        stochastic.validate(&self.rule)?;
+        Ok(Self {
+            random: Some(Random::new(stochastic, seed)),
+            ..self
+        })
+    }
+
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if self.bounds.contains(x, y) {
+            let col = (x - self.bounds.min_x) as usize;
+            let row = (y - self.bounds.min_y) as usize;
+            Some(row * self.bounds.width() + col)
+        } else {
+            None
+        }
+    }
+
+    fn num_alive_neighbors(&self, x: i32, y: i32) -> usize {
+        let mut alive = 0;
+        for (dx, dy) in self.rule.neighborhood().offsets(x, y) {
+            let neighbor = self.topology.resolve(&self.bounds, x + dx, y + dy);
+            if let Some((nx, ny)) = neighbor {
+                if self.get(nx, ny).is_alive() {
+                    alive += 1;
+                }
+            }
+        }
+        alive
+    }
+
+    /// Alive Moore neighbors as a bit per offset, for non-totalistic rules.
+    fn moore_neighbors(&self, x: i32, y: i32) -> u8 {
+        let mut neighbors = 0;
+        for (i, (dx, dy)) in Neighborhood::Moore.offsets(x, y).enumerate() {
+            let neighbor = self.topology.resolve(&self.bounds, x + dx, y + dy);
+            if let Some((nx, ny)) = neighbor {
+                if self.get(nx, ny).is_alive() {
+                    neighbors |= 1 << i;
+                }
+            }
+        }
+        neighbors
+    }
+
+    /// Color of a cell born at `(x, y)`, for multi-color rules.
+    fn birth_color(&self, x: i32, y: i32) -> u8 {
+        let colors = self
+            .rule
+            .neighborhood()
+            .offsets(x, y)
+            .filter_map(|(dx, dy)| self.topology.resolve(&self.bounds, x + dx, y + dy))
+            .filter_map(|(nx, ny)| match self.get(nx, ny) {
+                CellState::Alive(color) => Some(color),
+                _ => None,
+            });
+        self.rule.birth_color(colors)
+    }
+}
+
+impl Universe for Board {
+    fn step(&mut self) {
+        let mut next = mem::take(&mut self.next);
+        let mut i = 0;
+        for y in self.bounds.min_y..=self.bounds.max_y {
+            for x in self.bounds.min_x..=self.bounds.max_x {
+                let (alive_neighbors, state) = if self.rule.is_totalistic() {
+                    let alive_neighbors = self.num_alive_neighbors(x, y);
+                    let state = self.rule.next_state(self.cells[i], alive_neighbors);
+                    (alive_neighbors, state)
+                } else {
+                    let neighbors = self.moore_neighbors(x, y);
+                    let state = self.rule.next_state_moore(self.cells[i], neighbors);
+                    (neighbors.count_ones() as usize, state)
+                };
+                let state = match self.random.as_mut() {
+                    Some(random) => {
+                        random.next_state(&self.rule, self.cells[i], alive_neighbors, state)
+                    }
+                    None => state,
+                };
+                next[i] = match state {
+                    CellState::Alive(_) if self.rule.colors() > 1 && !self.cells[i].is_alive() => {
+                        CellState::Alive(self.birth_color(x, y))
+                    }
+                    state => state,
+                };
+                i += 1;
+            }
+        }
+        if let Some(random) = self.random.as_mut() {
+            random.add_noise(&mut next);
+        }
+        self.next = mem::replace(&mut self.cells, next);
+        self.generation += 1;
+    }
+
+    fn get(&self, x: i32, y: i32) -> CellState {
+        self.index(x, y)
+            .map(|i| self.cells[i])
+            .unwrap_or(CellState::Dead)
+    }
+
+    fn set(&mut self, x: i32, y: i32, state: CellState) {
+        if let Some(i) = self.index(x, y) {
+            self.cells[i] = state;
+        }
+    }
+
+    fn population(&self) -> usize {
+        self.cells.iter().filter(|c| **c != CellState::Dead).count()
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Some(self.bounds)
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+}
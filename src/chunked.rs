@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use rayon::prelude::*;
+
+use crate::bits::BitRule;
+use crate::{Bounds, CellState, Neighborhood, Rule, RuleError, Universe};
+
+/// Width and height of a chunk in cells.
+pub const CHUNK_SIZE: i32 = 64;
+
+/// Rows of a chunk, bit i of row r is the cell at `(i, r)` in the chunk.
+type Cells = [u64; CHUNK_SIZE as usize];
+
+const EMPTY: Cells = [0; CHUNK_SIZE as usize];
+
+#[derive(Debug, Clone)]
+struct Chunk {
+    cells: Cells,
+    // if it didn't change and neither did its neighbors it won't change in
+    // the next step either
+    changed: bool,
+}
+
+/// Unbounded universe split into chunks of 64x64 cells that are stepped in
+/// parallel.
+///
+/// Only chunks that changed in the last step and their neighbors are
+/// stepped, so dead or stable regions cost nothing. Chunks are allocated when
+/// cells are born in them and freed once they are empty.
+///
+/// Only supports totalistic two-state rules with one color and without `B0`.
+#[derive(Debug, Clone)]
+pub struct ChunkedUniverse {
+    rule: Rule,
+    bit_rule: BitRule,
+    chunks: HashMap<(i32, i32), Chunk>,
+    generation: u64,
+}
+
+/// Chunk containing a cell and the position of the cell in it.
+fn split(x: i32, y: i32) -> ((i32, i32), usize, usize) {
+    (
+        (x.div_euclid(CHUNK_SIZE), y.div_euclid(CHUNK_SIZE)),
+        x.rem_euclid(CHUNK_SIZE) as usize,
+        y.rem_euclid(CHUNK_SIZE) as usize,
+    )
+}
+
+impl ChunkedUniverse {
+    pub fn new(rule: Rule) -> Result<Self, RuleError> {
+        if rule.states() > 2 {
+            return Err(RuleError::Unsupported(
+                "the chunked engine only supports rules with two states".to_string(),
+            ));
+        }
+        if rule.colors() > 1 {
+            return Err(RuleError::Unsupported(
+                "the chunked engine only supports rules with one color".to_string(),
+            ));
+        }
+        if rule.neighborhood() != Neighborhood::Moore {
+            return Err(RuleError::Unsupported(
+                "the chunked engine only supports the Moore neighborhood".to_string(),
+            ));
+        }
+        if !rule.is_totalistic() {
+            return Err(RuleError::Unsupported(
+                "the chunked engine only supports totalistic rules".to_string(),
+            ));
+        }
+        if rule.is_birth(0) {
+            return Err(RuleError::Unsupported(
+                "B0 rules need a bounded board".to_string(),
+            ));
+        }
+        Ok(Self {
+            rule,
+            bit_rule: BitRule::new(&rule),
+            chunks: HashMap::new(),
+            generation: 0,
+        })
+    }
+
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    fn cells(&self, chunk: (i32, i32)) -> &Cells {
+        self.chunks.get(&chunk).map(|c| &c.cells).unwrap_or(&EMPTY)
+    }
+
+    /// Cells of a chunk in the next generation.
+    fn step_chunk(&self, (cx, cy): (i32, i32)) -> Cells {
+        let cells = self.cells((cx, cy));
+        let north = self.cells((cx, cy - 1));
+        let south = self.cells((cx, cy + 1));
+        let west = self.cells((cx - 1, cy));
+        let east = self.cells((cx + 1, cy));
+        let north_west = self.cells((cx - 1, cy - 1));
+        let north_east = self.cells((cx + 1, cy - 1));
+        let south_west = self.cells((cx - 1, cy + 1));
+        let south_east = self.cells((cx + 1, cy + 1));
+
+        let last = CHUNK_SIZE as usize - 1;
+        // row r with its west and east neighbors from the adjacent chunks
+        let row = |r: isize| -> (u64, u64, u64) {
+            let (center, west, east) = if r < 0 {
+                (north[last], north_west[last], north_east[last])
+            } else if r as usize > last {
+                (south[0], south_west[0], south_east[0])
+            } else {
+                let r = r as usize;
+                (cells[r], west[r], east[r])
+            };
+            (center, center << 1 | west >> 63, center >> 1 | east << 63)
+        };
+
+        let mut next = EMPTY;
+        for (r, next) in next.iter_mut().enumerate() {
+            let r = r as isize;
+            let (above, above_west, above_east) = row(r - 1);
+            let (center, center_west, center_east) = row(r);
+            let (below, below_west, below_east) = row(r + 1);
+            *next = self.bit_rule.next(
+                center,
+                [
+                    above_west,
+                    above,
+                    above_east,
+                    center_west,
+                    center_east,
+                    below_west,
+                    below,
+                    below_east,
+                ],
+            );
+        }
+        next
+    }
+}
+
+impl Universe for ChunkedUniverse {
+    fn step(&mut self) {
+        let mut candidates = HashSet::new();
+        for (&(cx, cy), chunk) in &self.chunks {
+            if chunk.changed {
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        candidates.insert((cx + dx, cy + dy));
+                    }
+                }
+            }
+        }
+
+        let candidates: Vec<_> = candidates.into_iter().collect();
+        let stepped: Vec<_> = candidates
+            .par_iter()
+            .map(|&pos| (pos, self.step_chunk(pos)))
+            .collect();
+
+        for chunk in self.chunks.values_mut() {
+            chunk.changed = false;
+        }
+        for (pos, cells) in stepped {
+            if cells == *self.cells(pos) {
+                continue;
+            }
+            if cells == EMPTY {
+                // removing it is a change for the neighbors, so they get
+                // stepped by the next step anyway
+                self.chunks.remove(&pos);
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        if let Some(neighbor) = self.chunks.get_mut(&(pos.0 + dx, pos.1 + dy)) {
+                            neighbor.changed = true;
+                        }
+                    }
+                }
+            } else {
+                self.chunks.insert(
+                    pos,
+                    Chunk {
+                        cells,
+                        changed: true,
+                    },
+                );
+            }
+        }
+
+        self.generation += 1;
+    }
+
+    fn get(&self, x: i32, y: i32) -> CellState {
+        let (chunk, col, row) = split(x, y);
+        if self.cells(chunk)[row] & (1 << col) != 0 {
+            CellState::Alive(0)
+        } else {
+            CellState::Dead
+        }
+    }
+
+    fn set(&mut self, x: i32, y: i32, state: CellState) {
+        let (chunk, col, row) = split(x, y);
+        if state == CellState::Dead && !self.chunks.contains_key(&chunk) {
+            return;
+        }
+        let chunk = self.chunks.entry(chunk).or_insert(Chunk {
+            cells: EMPTY,
+            changed: true,
+        });
+        chunk.changed = true;
+        if state == CellState::Dead {
+            chunk.cells[row] &= !(1 << col);
+        } else {
+            chunk.cells[row] |= 1 << col;
+        }
+    }
+
+    fn population(&self) -> usize {
+        self.chunks
+            .values()
+            .flat_map(|chunk| chunk.cells.iter())
+            .map(|row| row.count_ones() as usize)
+            .sum()
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        None
+    }
+
+    fn live_cells(&self, area: &Bounds) -> Vec<(i32, i32, CellState)> {
+        let mut cells = Vec::new();
+        for (&(cx, cy), chunk) in &self.chunks {
+            let (min_x, min_y) = (cx * CHUNK_SIZE, cy * CHUNK_SIZE);
+            let outside = min_x > area.max_x
+                || min_y > area.max_y
+                || min_x + CHUNK_SIZE <= area.min_x
+                || min_y + CHUNK_SIZE <= area.min_y;
+            if outside {
+                continue;
+            }
+            for (r, &row) in chunk.cells.iter().enumerate() {
+                let mut row = row;
+                while row != 0 {
+                    let col = row.trailing_zeros() as i32;
+                    row &= row - 1;
+                    let (x, y) = (min_x + col, min_y + r as i32);
+                    if area.contains(x, y) {
+                        cells.push((x, y, CellState::Alive(0)));
+                    }
+                }
+            }
+        }
+        cells
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+}
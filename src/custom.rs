@@ -0,0 +1,296 @@
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::mem;
+use std::sync::Arc;
+
+use crate::{Bounds, CellState, Neighborhood, RuleError, Topology, Universe};
+
+/// Automaton defined by how a single cell changes, for rules that don't
+/// belong to any of the built-in families of [`crate::Automaton`].
+///
+/// Cells have a state between `0` and `states() - 1`, where `0` is dead.
+/// Rules are run by a [`CustomBoard`] and selected by name from a
+/// [`RuleRegistry`].
+pub trait CellRule: Send + Sync {
+    /// Name the rule is registered and selected by.
+    fn name(&self) -> &str;
+
+    /// Number of states including dead.
+    fn states(&self) -> u8 {
+        2
+    }
+
+    /// Cells whose states are passed to [`CellRule::next_state`].
+    fn neighborhood(&self) -> Neighborhood {
+        Neighborhood::Moore
+    }
+
+    /// State of a cell in the next generation, where `neighbors` are the
+    /// states of its neighbors in the order of [`Neighborhood::offsets`].
+    /// Neighbors across the edges of the board are found through its
+    /// [`Topology`], those that don't exist there are dead.
+    fn next_state(&self, state: u8, neighbors: &[u8]) -> u8;
+
+    /// How the states are told apart when the cells are drawn.
+    fn shading(&self) -> Shading {
+        Shading::Fade
+    }
+}
+
+/// Rendering hint of a [`CellRule`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Shading {
+    /// `1` is alive and higher states fade out like the dying cells of
+    /// Generations rules.
+    #[default]
+    Fade,
+    /// Every state but dead has a color of its own.
+    Palette,
+}
+
+/// Conway's Game of Life as a [`CellRule`], the same as the `B3/S23` rule.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConwayLife;
+
+impl CellRule for ConwayLife {
+    fn name(&self) -> &str {
+        "ConwayLife"
+    }
+
+    fn next_state(&self, state: u8, neighbors: &[u8]) -> u8 {
+        let alive = neighbors.iter().filter(|&&neighbor| neighbor == 1).count();
+        match (state, alive) {
+            (1, 2) | (_, 3) => 1,
+            _ => 0,
+        }
+    }
+}
+
+/// Shared handle to a [`CellRule`]. Handles are equal if they share the same
+/// rule, two rules created separately are different even if they behave the
+/// same.
+#[derive(Clone)]
+pub struct CustomRule(Arc<dyn CellRule>);
+
+impl CustomRule {
+    pub fn new(rule: impl CellRule + 'static) -> Self {
+        Self(Arc::new(rule))
+    }
+
+    pub fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    pub fn rule(&self) -> &dyn CellRule {
+        &*self.0
+    }
+
+    /// Number of colors of alive cells, all states but dead if the rule uses
+    /// a [`Shading::Palette`].
+    pub fn colors(&self) -> u8 {
+        match self.0.shading() {
+            Shading::Fade => 1,
+            Shading::Palette => self.max_state(),
+        }
+    }
+
+    /// Cell state drawn for a state of the rule.
+    pub fn cell_state(&self, state: u8) -> CellState {
+        match (self.0.shading(), state) {
+            (_, 0) => CellState::Dead,
+            (Shading::Fade, state) => CellState::from(state),
+            (Shading::Palette, state) => CellState::Alive(state - 1),
+        }
+    }
+
+    /// State of the rule for a cell state, the inverse of
+    /// [`CustomRule::cell_state`].
+    pub fn state(&self, cell: CellState) -> u8 {
+        let state = match (self.0.shading(), cell) {
+            (_, CellState::Dead) => 0,
+            (Shading::Fade, CellState::Alive(_)) => 1,
+            (Shading::Fade, CellState::Dying(age)) => age.saturating_add(1),
+            (Shading::Palette, CellState::Alive(color)) => color.saturating_add(1),
+            _ => 1,
+        };
+        state.min(self.max_state())
+    }
+
+    /// Highest state of the rule, rules with fewer than 2 states are treated
+    /// as if they had 2.
+    fn max_state(&self) -> u8 {
+        self.0.states().max(2) - 1
+    }
+}
+
+impl fmt::Debug for CustomRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CustomRule").field(&self.name()).finish()
+    }
+}
+
+impl fmt::Display for CustomRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl PartialEq for CustomRule {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for CustomRule {}
+
+impl Hash for CustomRule {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // the address without the vtable, like `Arc::ptr_eq`
+        (Arc::as_ptr(&self.0) as *const u8).hash(state);
+    }
+}
+
+/// Rules that can be selected by their name, in any case.
+#[derive(Debug, Clone)]
+pub struct RuleRegistry {
+    rules: BTreeMap<String, CustomRule>,
+}
+
+impl RuleRegistry {
+    /// Registry without any rules.
+    pub fn new() -> Self {
+        Self {
+            rules: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a rule, fails if a rule with the same name in any case is
+    /// registered already.
+    pub fn register(&mut self, rule: impl CellRule + 'static) -> Result<CustomRule, RuleError> {
+        let rule = CustomRule::new(rule);
+        match self.rules.entry(rule.name().to_ascii_lowercase()) {
+            Entry::Occupied(_) => Err(RuleError::DuplicateRule(rule.name().to_string())),
+            Entry::Vacant(entry) => Ok(entry.insert(rule).clone()),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<CustomRule> {
+        self.rules.get(&name.trim().to_ascii_lowercase()).cloned()
+    }
+
+    /// Names of all rules in alphabetical order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.rules.values().map(CustomRule::name)
+    }
+}
+
+impl Default for RuleRegistry {
+    /// Registry with [`ConwayLife`].
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry
+            .register(ConwayLife)
+            .expect("the registry is empty");
+        registry
+    }
+}
+
+/// Dense finite board for a [`CellRule`].
+#[derive(Debug, Clone)]
+pub struct CustomBoard {
+    bounds: Bounds,
+    rule: CustomRule,
+    topology: Topology,
+    cells: Vec<u8>,
+    next: Vec<u8>,
+    generation: u64,
+}
+
+impl CustomBoard {
+    pub fn new(bounds: Bounds, rule: CustomRule) -> Self {
+        let len = bounds.width() * bounds.height();
+        Self {
+            bounds,
+            rule,
+            topology: Topology::default(),
+            cells: vec![0; len],
+            next: vec![0; len],
+            generation: 0,
+        }
+    }
+
+    pub fn with_topology(self, topology: Topology) -> Self {
+        Self { topology, ..self }
+    }
+
+    pub fn rule(&self) -> &CustomRule {
+        &self.rule
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if self.bounds.contains(x, y) {
+            let col = (x - self.bounds.min_x) as usize;
+            let row = (y - self.bounds.min_y) as usize;
+            Some(row * self.bounds.width() + col)
+        } else {
+            None
+        }
+    }
+}
+
+impl Universe for CustomBoard {
+    fn step(&mut self) {
+        let rule = self.rule.rule();
+        let neighborhood = rule.neighborhood();
+        let max = self.rule.max_state();
+        let mut next = mem::take(&mut self.next);
+        let mut neighbors = Vec::new();
+        let mut i = 0;
+        for y in self.bounds.min_y..=self.bounds.max_y {
+            for x in self.bounds.min_x..=self.bounds.max_x {
+                neighbors.clear();
+                neighbors.extend(neighborhood.offsets(x, y).map(|(dx, dy)| {
+                    self.topology
+                        .resolve(&self.bounds, x + dx, y + dy)
+                        .and_then(|(x, y)| self.index(x, y))
+                        .map_or(0, |i| self.cells[i])
+                }));
+                next[i] = rule.next_state(self.cells[i], &neighbors).min(max);
+                i += 1;
+            }
+        }
+        self.next = mem::replace(&mut self.cells, next);
+        self.generation += 1;
+    }
+
+    fn get(&self, x: i32, y: i32) -> CellState {
+        self.index(x, y)
+            .map(|i| self.rule.cell_state(self.cells[i]))
+            .unwrap_or(CellState::Dead)
+    }
+
+    fn set(&mut self, x: i32, y: i32, state: CellState) {
+        let state = self.rule.state(state);
+        if let Some(i) = self.index(x, y) {
+            self.cells[i] = state;
+        }
+    }
+
+    fn population(&self) -> usize {
+        self.cells.iter().filter(|&&state| state != 0).count()
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Some(self.bounds)
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+}
@@ -0,0 +1,361 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::mem;
+use std::str::FromStr;
+
+use rayon::prelude::*;
+
+use crate::ltl::parse_number;
+use crate::random::Rng;
+use crate::{Bounds, CellState, LtlNeighborhood, RuleError, Topology, Universe, MAX_LTL_RANGE};
+
+/// Cyclic rule, where every cell has one of `states` states in a cycle and
+/// advances to the next one when at least `threshold` of its neighbors are
+/// already in it. Started from random states this forms spiral waves.
+///
+/// Written in MCell's notation `Rr/Tt/Cc/Nn`, e.g. `R1/T3/C3/NM`:
+///
+/// * `R` is the range of the neighborhood, up to [`MAX_LTL_RANGE`].
+/// * `T` is the threshold.
+/// * `C` is the number of states.
+/// * `NM` is the Moore and `NN` the Von Neumann neighborhood.
+///
+/// `N` may be left out and defaults to `NM`. A trailing `/RPS` makes it a
+/// rock-paper-scissors rule: every state is beaten by the next half of the
+/// states in the cycle, and a cell is taken over by the state beating it
+/// that most of its neighbors are in (the closest one on ties), if there are
+/// at least `threshold` of them. With 3 states that's exactly the cyclic
+/// rule, with 5 it's rock-paper-scissors-lizard-Spock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cyclic {
+    range: u32,
+    threshold: u32,
+    states: u8,
+    neighborhood: LtlNeighborhood,
+    rock_paper_scissors: bool,
+}
+
+impl Cyclic {
+    pub fn range(&self) -> u32 {
+        self.range
+    }
+
+    pub fn threshold(&self) -> u32 {
+        self.threshold
+    }
+
+    /// Number of states in the cycle.
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    pub fn neighborhood(&self) -> LtlNeighborhood {
+        self.neighborhood
+    }
+
+    pub fn is_rock_paper_scissors(&self) -> bool {
+        self.rock_paper_scissors
+    }
+
+    /// Number of cells counted as neighbors, not including the cell itself.
+    pub fn size(&self) -> usize {
+        let range = self.range as usize;
+        match self.neighborhood {
+            LtlNeighborhood::Moore => (2 * range + 1) * (2 * range + 1) - 1,
+            LtlNeighborhood::VonNeumann => 2 * range * (range + 1),
+        }
+    }
+
+    /// Number of states following a state in the cycle that can take it over.
+    pub fn successors(&self) -> u8 {
+        if self.rock_paper_scissors {
+            (self.states - 1) / 2
+        } else {
+            1
+        }
+    }
+
+    /// State of a cell in the next generation, where `neighbors[n]` is the
+    /// number of neighbors in the state `n + 1` steps after `state`, for
+    /// each of the [`Cyclic::successors`].
+    pub fn next_state(&self, state: u8, neighbors: &[u32]) -> u8 {
+        // the last of several maximums is the first before reversing
+        let best = neighbors
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|&(_, &count)| count >= self.threshold)
+            .max_by_key(|&(_, &count)| count);
+        match best {
+            Some((n, _)) => ((state as usize + n + 1) % self.states as usize) as u8,
+            None => state,
+        }
+    }
+
+    fn offsets(&self) -> Vec<(i32, i32)> {
+        let range = self.range as i32;
+        let mut offsets = Vec::with_capacity(self.size());
+        for dy in -range..=range {
+            for dx in -range..=range {
+                let inside = match self.neighborhood {
+                    LtlNeighborhood::Moore => true,
+                    LtlNeighborhood::VonNeumann => dx.abs() + dy.abs() <= range,
+                };
+                if inside && (dx, dy) != (0, 0) {
+                    offsets.push((dx, dy));
+                }
+            }
+        }
+        offsets
+    }
+}
+
+impl FromStr for Cyclic {
+    type Err = RuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut range = None;
+        let mut threshold = None;
+        let mut states = None;
+        let mut neighborhood = None;
+        let mut rock_paper_scissors = false;
+
+        for part in s.trim().split('/') {
+            if part.eq_ignore_ascii_case("rps") {
+                if mem::replace(&mut rock_paper_scissors, true) {
+                    return Err(RuleError::Duplicate('R'));
+                }
+                continue;
+            }
+            let mut chars = part.chars();
+            let letter = match chars.next() {
+                Some(c) => c.to_ascii_uppercase(),
+                None => return Err(RuleError::Malformed(s.to_string())),
+            };
+            let value = chars.as_str();
+            let duplicate = match letter {
+                'R' => range.replace(parse_number(value, s)?).is_some(),
+                'T' => threshold.replace(parse_number(value, s)?).is_some(),
+                'C' => states.replace(parse_number(value, s)?).is_some(),
+                'N' => {
+                    let n = match value {
+                        "M" | "m" => LtlNeighborhood::Moore,
+                        "N" | "n" => LtlNeighborhood::VonNeumann,
+                        _ => return Err(RuleError::Malformed(s.to_string())),
+                    };
+                    neighborhood.replace(n).is_some()
+                }
+                c => return Err(RuleError::UnexpectedChar(c)),
+            };
+            if duplicate {
+                return Err(RuleError::Duplicate(letter));
+            }
+        }
+
+        let (range, threshold, states) = match (range, threshold, states) {
+            (Some(range), Some(threshold), Some(states)) => (range, threshold, states),
+            _ => return Err(RuleError::Malformed(s.to_string())),
+        };
+        if range == 0 || range > MAX_LTL_RANGE {
+            return Err(RuleError::RangeOutOfRange(range));
+        }
+        let states = match u8::try_from(states) {
+            Ok(states) if states >= 2 => states,
+            _ => return Err(RuleError::StatesOutOfRange(states)),
+        };
+        if rock_paper_scissors && states < 3 {
+            return Err(RuleError::Unsupported(
+                "rock-paper-scissors rules need at least 3 states".to_string(),
+            ));
+        }
+
+        let rule = Self {
+            range,
+            threshold,
+            states,
+            neighborhood: neighborhood.unwrap_or(LtlNeighborhood::Moore),
+            rock_paper_scissors,
+        };
+        if threshold == 0 {
+            return Err(RuleError::Unsupported(
+                "the threshold must be at least 1".to_string(),
+            ));
+        }
+        let max = rule.size();
+        if threshold as usize > max {
+            return Err(RuleError::CountOutOfRange(threshold as usize, max));
+        }
+        Ok(rule)
+    }
+}
+
+impl fmt::Display for Cyclic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let neighborhood = match self.neighborhood {
+            LtlNeighborhood::Moore => 'M',
+            LtlNeighborhood::VonNeumann => 'N',
+        };
+        write!(
+            f,
+            "R{}/T{}/C{}/N{}",
+            self.range, self.threshold, self.states, neighborhood
+        )?;
+        if self.rock_paper_scissors {
+            write!(f, "/RPS")?;
+        }
+        Ok(())
+    }
+}
+
+/// Dense finite board for [`Cyclic`] rules.
+///
+/// Every cell is in one of the states of the cycle, returned as
+/// [`CellState::Alive`] with the state as its color. Setting a cell dead
+/// puts it into the first state.
+#[derive(Debug, Clone)]
+pub struct CyclicBoard {
+    bounds: Bounds,
+    rule: Cyclic,
+    topology: Topology,
+    offsets: Vec<(i32, i32)>,
+    cells: Vec<u8>,
+    next: Vec<u8>,
+    // states of the board and a margin of `range` cells around it, cells
+    // outside of the bounds are `u8::MAX`
+    margin: Vec<u8>,
+    generation: u64,
+}
+
+impl CyclicBoard {
+    pub fn new(bounds: Bounds, rule: Cyclic) -> Self {
+        let len = bounds.width() * bounds.height();
+        Self {
+            bounds,
+            rule,
+            topology: Topology::default(),
+            offsets: rule.offsets(),
+            cells: vec![0; len],
+            next: vec![0; len],
+            margin: Vec::new(),
+            generation: 0,
+        }
+    }
+
+    pub fn with_topology(self, topology: Topology) -> Self {
+        Self { topology, ..self }
+    }
+
+    /// Puts every cell into a random state drawn from `seed`, so the same
+    /// seed always gives the same start.
+    pub fn with_random_states(mut self, seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        for cell in &mut self.cells {
+            *cell = (rng.next_u64() % self.rule.states as u64) as u8;
+        }
+        self
+    }
+
+    pub fn rule(&self) -> Cyclic {
+        self.rule
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if self.bounds.contains(x, y) {
+            let col = (x - self.bounds.min_x) as usize;
+            let row = (y - self.bounds.min_y) as usize;
+            Some(row * self.bounds.width() + col)
+        } else {
+            None
+        }
+    }
+
+    fn update_margin(&mut self) {
+        let range = self.rule.range as i32;
+        let stride = self.bounds.width() + 2 * range as usize;
+        let rows = self.bounds.height() + 2 * range as usize;
+        let mut margin = mem::take(&mut self.margin);
+        margin.clear();
+        margin.resize(stride * rows, u8::MAX);
+        for row in 0..rows {
+            let y = self.bounds.min_y - range + row as i32;
+            for col in 0..stride {
+                let x = self.bounds.min_x - range + col as i32;
+                if let Some((x, y)) = self.topology.resolve(&self.bounds, x, y) {
+                    if let Some(i) = self.index(x, y) {
+                        margin[row * stride + col] = self.cells[i];
+                    }
+                }
+            }
+        }
+        self.margin = margin;
+    }
+}
+
+impl Universe for CyclicBoard {
+    fn step(&mut self) {
+        self.update_margin();
+        let range = self.rule.range as i32;
+        let width = self.bounds.width();
+        let stride = width as i32 + 2 * range;
+        let states = self.rule.states as usize;
+        let successors = self.rule.successors() as usize;
+        let (rule, offsets, margin, cells) = (&self.rule, &self.offsets, &self.margin, &self.cells);
+
+        let mut next = mem::take(&mut self.next);
+        next.par_iter_mut().enumerate().for_each_init(
+            || vec![0; successors],
+            |neighbors, (i, next)| {
+                neighbors.iter_mut().for_each(|count| *count = 0);
+                let state = cells[i] as usize;
+                // the cell is at (col + range, row + range) in the margin
+                let col = (i % width) as i32 + range;
+                let row = (i / width) as i32 + range;
+                for &(dx, dy) in offsets {
+                    let neighbor = margin[((row + dy) * stride + col + dx) as usize] as usize;
+                    if neighbor < states {
+                        let ahead = (neighbor + states - state) % states;
+                        if (1..=successors).contains(&ahead) {
+                            neighbors[ahead - 1] += 1;
+                        }
+                    }
+                }
+                *next = rule.next_state(cells[i], neighbors);
+            },
+        );
+        self.next = mem::replace(&mut self.cells, next);
+        self.generation += 1;
+    }
+
+    fn get(&self, x: i32, y: i32) -> CellState {
+        match self.index(x, y) {
+            Some(i) => CellState::Alive(self.cells[i]),
+            None => CellState::Dead,
+        }
+    }
+
+    fn set(&mut self, x: i32, y: i32, state: CellState) {
+        let state = match state {
+            CellState::Alive(state) => state.min(self.rule.states - 1),
+            _ => 0,
+        };
+        if let Some(i) = self.index(x, y) {
+            self.cells[i] = state;
+        }
+    }
+
+    fn population(&self) -> usize {
+        self.cells.len()
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Some(self.bounds)
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+}
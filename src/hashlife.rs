@@ -0,0 +1,433 @@
+use std::collections::HashMap;
+
+use crate::{Bounds, CellState, Neighborhood, Rule, RuleError, Universe};
+
+type NodeId = u32;
+
+const DEAD: NodeId = 0;
+const ALIVE: NodeId = 1;
+
+/// Number of nodes after which unreachable nodes and cached results are
+/// thrown away before the next step.
+const MAX_NODES: usize = 1 << 22;
+
+/// Level of the largest root, so coordinates relative to its corners still
+/// fit into an `i64`.
+const MAX_LEVEL: u8 = 62;
+
+/// Largest exponent [`HashLife`] jumps by at once, larger ones are clamped.
+pub const MAX_JUMP_EXPONENT: u32 = MAX_LEVEL as u32 - 3;
+
+/// Square of `2^level` cells made of four squares of half the size.
+///
+/// Nodes are deduplicated, so equal squares always share the same id. Leaves
+/// (level 0) are the single cells [`DEAD`] and [`ALIVE`].
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    // north is towards smaller y
+    nw: NodeId,
+    ne: NodeId,
+    sw: NodeId,
+    se: NodeId,
+    level: u8,
+    population: u64,
+}
+
+/// Unbounded universe using Gosper's HashLife algorithm.
+///
+/// The board is a quadtree of deduplicated nodes and the future of every node
+/// is cached, so repetitive patterns can be advanced by huge numbers of
+/// generations at once with [`Universe::jump`], up to `2^MAX_JUMP_EXPONENT`.
+///
+/// Only supports two-state rules with one color and without `B0`.
+#[derive(Debug, Clone)]
+pub struct HashLife {
+    rule: Rule,
+    nodes: Vec<Node>,
+    ids: HashMap<[NodeId; 4], NodeId>,
+    // (node, log2 of the generations) -> center of the node after that time
+    results: HashMap<(NodeId, u8), NodeId>,
+    // empty node of every level
+    empty: Vec<NodeId>,
+    // covers -2^(level - 1)..2^(level - 1) on both axes
+    root: NodeId,
+    generation: u64,
+}
+
+impl HashLife {
+    pub fn new(rule: Rule) -> Result<Self, RuleError> {
+        if rule.states() > 2 {
+            return Err(RuleError::Unsupported(
+                "HashLife only supports rules with two states".to_string(),
+            ));
+        }
+        if rule.colors() > 1 {
+            return Err(RuleError::Unsupported(
+                "HashLife only supports rules with one color".to_string(),
+            ));
+        }
+        if rule.neighborhood() != Neighborhood::Moore {
+            return Err(RuleError::Unsupported(
+                "HashLife only supports the Moore neighborhood".to_string(),
+            ));
+        }
+        if rule.is_birth(0) {
+            return Err(RuleError::Unsupported(
+                "B0 rules need a bounded board".to_string(),
+            ));
+        }
+
+        let leaf = |population| Node {
+            nw: DEAD,
+            ne: DEAD,
+            sw: DEAD,
+            se: DEAD,
+            level: 0,
+            population,
+        };
+        let mut hashlife = Self {
+            rule,
+            nodes: vec![leaf(0), leaf(1)],
+            ids: HashMap::new(),
+            results: HashMap::new(),
+            empty: vec![DEAD],
+            root: DEAD,
+            generation: 0,
+        };
+        hashlife.root = hashlife.empty(3);
+        Ok(hashlife)
+    }
+
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    fn node(&self, id: NodeId) -> Node {
+        self.nodes[id as usize]
+    }
+
+    fn level(&self) -> u8 {
+        self.node(self.root).level
+    }
+
+    fn join(&mut self, nw: NodeId, ne: NodeId, sw: NodeId, se: NodeId) -> NodeId {
+        if let Some(&id) = self.ids.get(&[nw, ne, sw, se]) {
+            return id;
+        }
+        let population = [nw, ne, sw, se]
+            .iter()
+            .map(|&child| self.node(child).population)
+            .sum();
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(Node {
+            nw,
+            ne,
+            sw,
+            se,
+            level: self.node(nw).level + 1,
+            population,
+        });
+        self.ids.insert([nw, ne, sw, se], id);
+        id
+    }
+
+    fn empty(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let e = *self.empty.last().unwrap();
+            let next = self.join(e, e, e, e);
+            self.empty.push(next);
+        }
+        self.empty[level as usize]
+    }
+
+    /// Node of half the size in the center of `id`.
+    fn center(&mut self, id: NodeId) -> NodeId {
+        let n = self.node(id);
+        let (nw, ne, sw, se) = (
+            self.node(n.nw),
+            self.node(n.ne),
+            self.node(n.sw),
+            self.node(n.se),
+        );
+        self.join(nw.se, ne.sw, sw.ne, se.nw)
+    }
+
+    /// Doubles the size of the root, keeping its content in the center.
+    fn expand(&mut self) {
+        let root = self.node(self.root);
+        let e = self.empty(root.level - 1);
+        let nw = self.join(e, e, e, root.nw);
+        let ne = self.join(e, e, root.ne, e);
+        let sw = self.join(e, root.sw, e, e);
+        let se = self.join(root.se, e, e, e);
+        self.root = self.join(nw, ne, sw, se);
+    }
+
+    fn half(&self) -> i64 {
+        1 << (self.level() - 1)
+    }
+
+    fn contains(&self, x: i32, y: i32) -> bool {
+        let half = self.half();
+        let range = -half..half;
+        range.contains(&(x as i64)) && range.contains(&(y as i64))
+    }
+
+    fn cell(&self, id: NodeId, x: i64, y: i64) -> NodeId {
+        // x and y are relative to the top left corner of the node
+        let n = self.node(id);
+        if n.level == 0 || n.population == 0 {
+            return id;
+        }
+        let half = 1 << (n.level - 1);
+        match (x < half, y < half) {
+            (true, true) => self.cell(n.nw, x, y),
+            (false, true) => self.cell(n.ne, x - half, y),
+            (true, false) => self.cell(n.sw, x, y - half),
+            (false, false) => self.cell(n.se, x - half, y - half),
+        }
+    }
+
+    fn set_cell(&mut self, id: NodeId, x: i64, y: i64, leaf: NodeId) -> NodeId {
+        let n = self.node(id);
+        if n.level == 0 {
+            return leaf;
+        }
+        let half = 1 << (n.level - 1);
+        match (x < half, y < half) {
+            (true, true) => {
+                let nw = self.set_cell(n.nw, x, y, leaf);
+                self.join(nw, n.ne, n.sw, n.se)
+            }
+            (false, true) => {
+                let ne = self.set_cell(n.ne, x - half, y, leaf);
+                self.join(n.nw, ne, n.sw, n.se)
+            }
+            (true, false) => {
+                let sw = self.set_cell(n.sw, x, y - half, leaf);
+                self.join(n.nw, n.ne, sw, n.se)
+            }
+            (false, false) => {
+                let se = self.set_cell(n.se, x - half, y - half, leaf);
+                self.join(n.nw, n.ne, n.sw, se)
+            }
+        }
+    }
+
+    /// Center 2x2 of a 4x4 node after one generation.
+    fn step_level_2(&mut self, id: NodeId) -> NodeId {
+        let mut next = [DEAD; 4];
+        for (i, &(x, y)) in [(1, 1), (2, 1), (1, 2), (2, 2)].iter().enumerate() {
+            let mut neighbors = 0;
+            for (bit, (dx, dy)) in Neighborhood::Moore.offsets(0, 0).enumerate() {
+                if self.cell(id, x + i64::from(dx), y + i64::from(dy)) == ALIVE {
+                    neighbors |= 1 << bit;
+                }
+            }
+            let state = match self.cell(id, x, y) {
+                ALIVE => CellState::Alive(0),
+                _ => CellState::Dead,
+            };
+            if self.rule.next_state_moore(state, neighbors).is_alive() {
+                next[i] = ALIVE;
+            }
+        }
+        self.join(next[0], next[1], next[2], next[3])
+    }
+
+    /// Center of `id` (half its size) after `2^j` generations.
+    ///
+    /// `j` is limited to `level - 2` because the center can't be influenced
+    /// by anything outside of the node within that time.
+    fn successor(&mut self, id: NodeId, j: u8) -> NodeId {
+        let n = self.node(id);
+        let j = j.min(n.level - 2);
+        if n.population == 0 {
+            return self.empty(n.level - 1);
+        }
+        if let Some(&result) = self.results.get(&(id, j)) {
+            return result;
+        }
+
+        let result = if n.level == 2 {
+            self.step_level_2(id)
+        } else {
+            let (nw, ne, sw, se) = (
+                self.node(n.nw),
+                self.node(n.ne),
+                self.node(n.sw),
+                self.node(n.se),
+            );
+            // nine overlapping nodes of half the size
+            let n00 = n.nw;
+            let n01 = self.join(nw.ne, ne.nw, nw.se, ne.sw);
+            let n02 = n.ne;
+            let n10 = self.join(nw.sw, nw.se, sw.nw, sw.ne);
+            let n11 = self.join(nw.se, ne.sw, sw.ne, se.nw);
+            let n12 = self.join(ne.sw, ne.se, se.nw, se.ne);
+            let n20 = n.sw;
+            let n21 = self.join(sw.ne, se.nw, sw.se, se.sw);
+            let n22 = n.se;
+
+            let c00 = self.successor(n00, j);
+            let c01 = self.successor(n01, j);
+            let c02 = self.successor(n02, j);
+            let c10 = self.successor(n10, j);
+            let c11 = self.successor(n11, j);
+            let c12 = self.successor(n12, j);
+            let c20 = self.successor(n20, j);
+            let c21 = self.successor(n21, j);
+            let c22 = self.successor(n22, j);
+
+            let quadrants = [
+                self.join(c00, c01, c10, c11),
+                self.join(c01, c02, c11, c12),
+                self.join(c10, c11, c20, c21),
+                self.join(c11, c12, c21, c22),
+            ];
+            let mut next = [DEAD; 4];
+            for (next, &quadrant) in next.iter_mut().zip(quadrants.iter()) {
+                *next = if j == n.level - 2 {
+                    // second half of the generations
+                    self.successor(quadrant, j)
+                } else {
+                    self.center(quadrant)
+                };
+            }
+            self.join(next[0], next[1], next[2], next[3])
+        };
+
+        self.results.insert((id, j), result);
+        result
+    }
+
+    /// Throws away all nodes that are not part of the current root.
+    fn collect_garbage(&mut self) {
+        let leaves = self.nodes[..2].to_vec();
+        let old = std::mem::replace(&mut self.nodes, leaves);
+        self.ids.clear();
+        self.results.clear();
+        self.empty = vec![DEAD];
+
+        self.root = self.copy(&old, self.root, &mut HashMap::new());
+    }
+
+    fn copy(&mut self, old: &[Node], id: NodeId, copied: &mut HashMap<NodeId, NodeId>) -> NodeId {
+        if id == DEAD || id == ALIVE {
+            return id;
+        }
+        if let Some(&new) = copied.get(&id) {
+            return new;
+        }
+        let n = old[id as usize];
+        let nw = self.copy(old, n.nw, copied);
+        let ne = self.copy(old, n.ne, copied);
+        let sw = self.copy(old, n.sw, copied);
+        let se = self.copy(old, n.se, copied);
+        let new = self.join(nw, ne, sw, se);
+        copied.insert(id, new);
+        new
+    }
+
+    fn live_cells_in(
+        &self,
+        id: NodeId,
+        x: i64,
+        y: i64,
+        area: &Bounds,
+        cells: &mut Vec<(i32, i32, CellState)>,
+    ) {
+        let n = self.node(id);
+        let size = 1 << n.level;
+        let outside = x > area.max_x as i64
+            || y > area.max_y as i64
+            || x + size <= area.min_x as i64
+            || y + size <= area.min_y as i64;
+        if n.population == 0 || outside {
+            return;
+        }
+        if n.level == 0 {
+            cells.push((x as i32, y as i32, CellState::Alive(0)));
+            return;
+        }
+        let half = size / 2;
+        self.live_cells_in(n.nw, x, y, area, cells);
+        self.live_cells_in(n.ne, x + half, y, area, cells);
+        self.live_cells_in(n.sw, x, y + half, area, cells);
+        self.live_cells_in(n.se, x + half, y + half, area, cells);
+    }
+}
+
+impl Universe for HashLife {
+    fn step(&mut self) {
+        self.jump(0);
+    }
+
+    fn jump(&mut self, exponent: u32) {
+        if self.nodes.len() > MAX_NODES {
+            self.collect_garbage();
+        }
+
+        // The pattern has to stay in the center quarter of the root and the
+        // root has to be big enough for the number of generations, so
+        // nothing can grow beyond the center that is returned. Cells beyond
+        // the center of the largest root are far out of reach of `i32`
+        // coordinates and get lost.
+        let j = exponent.min(MAX_JUMP_EXPONENT) as u8;
+        loop {
+            let inner = self.center(self.root);
+            let inner = self.center(inner);
+            let fits = self.node(inner).population == self.node(self.root).population;
+            if (fits && self.level() >= j + 3) || self.level() >= MAX_LEVEL {
+                break;
+            }
+            self.expand();
+        }
+
+        self.root = self.successor(self.root, j);
+        let generations = 1u64.checked_shl(j.into()).unwrap_or(u64::MAX);
+        self.generation = self.generation.saturating_add(generations);
+    }
+
+    fn get(&self, x: i32, y: i32) -> CellState {
+        if !self.contains(x, y) {
+            return CellState::Dead;
+        }
+        let half = self.half();
+        match self.cell(self.root, x as i64 + half, y as i64 + half) {
+            ALIVE => CellState::Alive(0),
+            _ => CellState::Dead,
+        }
+    }
+
+    fn set(&mut self, x: i32, y: i32, state: CellState) {
+        while !self.contains(x, y) {
+            self.expand();
+        }
+        let leaf = match state {
+            CellState::Dead => DEAD,
+            _ => ALIVE,
+        };
+        let half = self.half();
+        self.root = self.set_cell(self.root, x as i64 + half, y as i64 + half, leaf);
+    }
+
+    fn population(&self) -> usize {
+        self.node(self.root).population as usize
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        None
+    }
+
+    fn live_cells(&self, area: &Bounds) -> Vec<(i32, i32, CellState)> {
+        let mut cells = Vec::new();
+        let half = self.half();
+        self.live_cells_in(self.root, -half, -half, area, &mut cells);
+        cells
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+}
@@ -0,0 +1,125 @@
+//! Configurations of the Moore neighborhood named by Hensel notation.
+//!
+//! A configuration is a byte where bit `i` is set if the `i`-th neighbor in
+//! the order of [`crate::Neighborhood::offsets`] for the Moore neighborhood
+//! is alive, i.e. row by row starting at `(-1, -1)`.
+
+/// Letters of the configurations of 0 to 8 alive neighbors that are
+/// distinct under rotation and reflection.
+const LETTERS: [&str; 9] = [
+    "",
+    "ce",
+    "ceaikn",
+    "ceaiknjqry",
+    "ceaiknjqrtwyz",
+    "ceaiknjqry",
+    "ceaikn",
+    "ce",
+    "",
+];
+
+// one configuration of every letter from 1 to 4 neighbors as in Golly, bits
+// 0 to 8 are the 3x3 square row by row including the cell itself at bit 4
+const EXAMPLES: [&[u16]; 5] = [
+    &[],
+    &[1, 2],
+    &[5, 10, 3, 40, 33, 68],
+    &[69, 42, 11, 7, 98, 13, 14, 70, 41, 97],
+    &[325, 170, 15, 45, 99, 71, 106, 102, 43, 101, 105, 78, 108],
+];
+
+/// Configurations that cause births and survivals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct Hensel {
+    pub(crate) birth: Configurations,
+    pub(crate) survival: Configurations,
+}
+
+/// Set of configurations.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct Configurations([u64; 4]);
+
+impl Configurations {
+    /// Configurations with any of the given numbers of alive neighbors.
+    pub(crate) fn with_counts(counts: &[u8]) -> Self {
+        let mut configurations = Self::default();
+        for &count in counts {
+            all(count.into()).for_each(|c| configurations.insert(c));
+        }
+        configurations
+    }
+
+    pub(crate) fn contains(&self, configuration: u8) -> bool {
+        self.0[configuration as usize / 64] & (1 << (configuration % 64)) != 0
+    }
+
+    pub(crate) fn insert(&mut self, configuration: u8) {
+        self.0[configuration as usize / 64] |= 1 << (configuration % 64);
+    }
+}
+
+pub(crate) fn letters(count: usize) -> &'static str {
+    LETTERS.get(count).copied().unwrap_or("")
+}
+
+/// All configurations of `count` alive neighbors.
+pub(crate) fn all(count: usize) -> impl Iterator<Item = u8> {
+    (0..=255).filter(move |c: &u8| c.count_ones() as usize == count)
+}
+
+/// Configurations of `count` alive neighbors with the given letter.
+pub(crate) fn with_letter(count: usize, letter: char) -> Vec<u8> {
+    let index = match letters(count).find(letter) {
+        Some(index) => index,
+        None => return Vec::new(),
+    };
+    // more than 4 neighbors are the inverse of the configuration of the
+    // dead neighbors
+    let example = if count > 4 {
+        !from_square(EXAMPLES[8 - count][index])
+    } else {
+        from_square(EXAMPLES[count][index])
+    };
+
+    let mut configurations: Vec<u8> = (0..8).map(|s| transform(example, s)).collect();
+    configurations.sort_unstable();
+    configurations.dedup();
+    configurations
+}
+
+fn from_square(square: u16) -> u8 {
+    (square & 0xf) as u8 | ((square >> 5) << 4) as u8
+}
+
+fn offset(bit: u8) -> (i32, i32) {
+    let i = if bit < 4 { bit } else { bit + 1 } as i32;
+    (i % 3 - 1, i / 3 - 1)
+}
+
+fn bit(dx: i32, dy: i32) -> u8 {
+    let i = ((dy + 1) * 3 + dx + 1) as u8;
+    if i < 4 {
+        i
+    } else {
+        i - 1
+    }
+}
+
+/// Applies one of the 8 rotations and reflections of the square.
+fn transform(configuration: u8, symmetry: u8) -> u8 {
+    let mut transformed = 0;
+    for b in (0..8).filter(|b| configuration & (1 << b) != 0) {
+        let (mut dx, mut dy) = offset(b);
+        if symmetry & 1 != 0 {
+            dx = -dx;
+        }
+        if symmetry & 2 != 0 {
+            dy = -dy;
+        }
+        if symmetry & 4 != 0 {
+            std::mem::swap(&mut dx, &mut dy);
+        }
+        transformed |= 1 << bit(dx, dy);
+    }
+    transformed
+}
@@ -0,0 +1,227 @@
+use std::mem;
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{Bounds, CellState, RuleError, Topology, Universe};
+
+/// Largest kernel radius of a [`Lenia`] rule.
+pub const MAX_LENIA_RADIUS: u32 = 64;
+
+/// Parameters of a Lenia rule, a continuous cellular automaton where every
+/// cell has a level between 0 and 1.
+///
+/// Every step the levels of the cells within `radius` are weighted by a
+/// ring-shaped kernel, and the level of the cell grows by `1 / time` times
+/// the growth function of that sum: a bell curve around `mu` with width
+/// `sigma`, scaled from -1 to 1.
+///
+/// The defaults are the ones of Orbium, the most common Lenia glider.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Lenia {
+    /// Radius `R` of the kernel in cells.
+    pub radius: u32,
+    /// Number of steps `T` per unit of time.
+    pub time: f32,
+    /// Weighted sum `mu` with the most growth.
+    pub mu: f32,
+    /// Width `sigma` of the growth function.
+    pub sigma: f32,
+}
+
+impl Lenia {
+    fn validate(&self) -> Result<(), RuleError> {
+        if self.radius == 0 || self.radius > MAX_LENIA_RADIUS {
+            return Err(RuleError::Unsupported(format!(
+                "the Lenia radius must be between 1 and {}",
+                MAX_LENIA_RADIUS
+            )));
+        }
+        if !(self.time > 0.0 && self.sigma > 0.0) {
+            return Err(RuleError::Unsupported(
+                "the Lenia time and sigma must be positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Change of the level of a cell whose neighborhood has the weighted sum
+    /// `potential`, before it is divided by `time`.
+    pub fn growth(&self, potential: f32) -> f32 {
+        let d = (potential - self.mu) / self.sigma;
+        2.0 * (-d * d / 2.0).exp() - 1.0
+    }
+
+    /// Offsets of the cells within the radius and their weight, which add up
+    /// to 1.
+    fn kernel(&self) -> Vec<(i32, i32, f32)> {
+        let radius = self.radius as i32;
+        let mut kernel = Vec::new();
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                let r = ((dx * dx + dy * dy) as f32).sqrt() / radius as f32;
+                if r > 0.0 && r < 1.0 {
+                    // smooth bump peaking at half the radius
+                    let weight = (4.0 - 1.0 / (r * (1.0 - r))).exp();
+                    kernel.push((dx, dy, weight));
+                }
+            }
+        }
+        let total: f32 = kernel.iter().map(|&(_, _, w)| w).sum();
+        for (_, _, weight) in &mut kernel {
+            *weight /= total;
+        }
+        kernel
+    }
+}
+
+impl Default for Lenia {
+    fn default() -> Self {
+        Self {
+            radius: 13,
+            time: 10.0,
+            mu: 0.15,
+            sigma: 0.015,
+        }
+    }
+}
+
+/// Dense finite board for [`Lenia`] rules.
+///
+/// Cells with a level above 0 count as alive for [`Universe::get`], setting
+/// a cell alive sets its level to 1. The kernel reaches across the edges as
+/// the [`Topology`] joins them.
+#[derive(Debug, Clone)]
+pub struct LeniaBoard {
+    bounds: Bounds,
+    rule: Lenia,
+    topology: Topology,
+    kernel: Vec<(i32, i32, f32)>,
+    levels: Vec<f32>,
+    next: Vec<f32>,
+    // levels of the board and a margin of `radius` cells around it
+    margin: Vec<f32>,
+    generation: u64,
+}
+
+impl LeniaBoard {
+    pub fn new(bounds: Bounds, rule: Lenia) -> Result<Self, RuleError> {
+        rule.validate()?;
+        let len = bounds.width() * bounds.height();
+        Ok(Self {
+            bounds,
+            rule,
+            topology: Topology::default(),
+            kernel: rule.kernel(),
+            levels: vec![0.0; len],
+            next: vec![0.0; len],
+            margin: Vec::new(),
+            generation: 0,
+        })
+    }
+
+    pub fn with_topology(self, topology: Topology) -> Self {
+        Self { topology, ..self }
+    }
+
+    pub fn rule(&self) -> Lenia {
+        self.rule
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Sets the level of a cell, clamped between 0 and 1.
+    pub fn set_level(&mut self, x: i32, y: i32, level: f32) {
+        if let Some(i) = self.index(x, y) {
+            self.levels[i] = level.clamp(0.0, 1.0);
+        }
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if self.bounds.contains(x, y) {
+            let col = (x - self.bounds.min_x) as usize;
+            let row = (y - self.bounds.min_y) as usize;
+            Some(row * self.bounds.width() + col)
+        } else {
+            None
+        }
+    }
+
+    fn update_margin(&mut self) {
+        let radius = self.rule.radius as i32;
+        let stride = self.bounds.width() + 2 * radius as usize;
+        let rows = self.bounds.height() + 2 * radius as usize;
+        let mut margin = mem::take(&mut self.margin);
+        margin.clear();
+        margin.resize(stride * rows, 0.0);
+        for row in 0..rows {
+            let y = self.bounds.min_y - radius + row as i32;
+            for col in 0..stride {
+                let x = self.bounds.min_x - radius + col as i32;
+                if let Some((x, y)) = self.topology.resolve(&self.bounds, x, y) {
+                    margin[row * stride + col] = self.level(x, y);
+                }
+            }
+        }
+        self.margin = margin;
+    }
+}
+
+impl Universe for LeniaBoard {
+    fn step(&mut self) {
+        self.update_margin();
+        let radius = self.rule.radius as i32;
+        let width = self.bounds.width();
+        let stride = width as i32 + 2 * radius;
+        let dt = 1.0 / self.rule.time;
+        let (rule, kernel, margin, levels) = (&self.rule, &self.kernel, &self.margin, &self.levels);
+
+        let mut next = mem::take(&mut self.next);
+        next.par_iter_mut().enumerate().for_each(|(i, next)| {
+            // the cell is at (col + radius, row + radius) in the margin
+            let col = (i % width) as i32 + radius;
+            let row = (i / width) as i32 + radius;
+            let potential: f32 = kernel
+                .iter()
+                .map(|&(dx, dy, weight)| weight * margin[((row + dy) * stride + col + dx) as usize])
+                .sum();
+            *next = (levels[i] + dt * rule.growth(potential)).clamp(0.0, 1.0);
+        });
+        self.next = mem::replace(&mut self.levels, next);
+        self.generation += 1;
+    }
+
+    fn get(&self, x: i32, y: i32) -> CellState {
+        if self.level(x, y) > 0.0 {
+            CellState::Alive(0)
+        } else {
+            CellState::Dead
+        }
+    }
+
+    fn set(&mut self, x: i32, y: i32, state: CellState) {
+        let level = match state {
+            CellState::Dead => 0.0,
+            _ => 1.0,
+        };
+        self.set_level(x, y, level);
+    }
+
+    fn level(&self, x: i32, y: i32) -> f32 {
+        self.index(x, y).map(|i| self.levels[i]).unwrap_or(0.0)
+    }
+
+    fn population(&self) -> usize {
+        self.levels.iter().filter(|&&level| level > 0.0).count()
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Some(self.bounds)
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+}
@@ -0,0 +1,68 @@
+//! Headless simulation core of the game.
+//!
+//! Everything in here is independent of amethyst so the simulation can be
+//! embedded in tools and tests without a window or the ECS. The game itself
+//! only drives a [`Universe`] and mirrors its cells into sprites.
+//!
+//! ```
+//! use game_of_live::{Board, Bounds, CellState, Rule, Universe};
+//!
+//! let rule: Rule = "B3/S23".parse().unwrap();
+//! let mut board = Board::new(Bounds::new(0, 4, 0, 4), rule);
+//! // blinker
+//! board.set(1, 2, CellState::Alive(0));
+//! board.set(2, 2, CellState::Alive(0));
+//! board.set(3, 2, CellState::Alive(0));
+//!
+//! board.step();
+//! assert_eq!(board.get(2, 1), CellState::Alive(0));
+//! assert_eq!(board.get(1, 2), CellState::Dead);
+//! assert_eq!(board.population(), 3);
+//! ```
+
+mod automaton;
+mod bits;
+mod board;
+mod chunked;
+mod custom;
+mod cyclic;
+mod hashlife;
+mod hensel;
+mod lenia;
+mod ltl;
+mod margolus;
+mod neighborhood;
+mod packed;
+mod plugin;
+mod random;
+mod rule;
+mod second_order;
+mod sparse;
+mod stochastic;
+mod topology;
+mod turmite;
+mod universe;
+mod wireworld;
+mod wolfram;
+
+pub use automaton::*;
+pub use board::*;
+pub use chunked::*;
+pub use custom::*;
+pub use cyclic::*;
+pub use hashlife::*;
+pub use lenia::*;
+pub use ltl::*;
+pub use margolus::*;
+pub use neighborhood::*;
+pub use packed::*;
+pub use plugin::*;
+pub use rule::*;
+pub use second_order::*;
+pub use sparse::*;
+pub use stochastic::*;
+pub use topology::*;
+pub use turmite::*;
+pub use universe::*;
+pub use wireworld::*;
+pub use wolfram::*;
@@ -0,0 +1,364 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::mem;
+use std::str::FromStr;
+
+use rayon::prelude::*;
+
+use crate::rule::decay;
+use crate::{Bounds, CellState, RuleError, Topology, Universe};
+
+/// Largest range of a [`LargerThanLife`] rule.
+pub const MAX_LTL_RANGE: u32 = 500;
+
+/// Shape of the neighborhood of a [`LargerThanLife`] rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LtlNeighborhood {
+    /// Square of all cells at most `range` cells away along each axis.
+    Moore,
+    /// Diamond of all cells at most `range` steps away.
+    VonNeumann,
+}
+
+/// Larger than Life rule, a Life-like rule where every cell within a range
+/// of up to [`MAX_LTL_RANGE`] is a neighbor.
+///
+/// Written in Golly's notation `Rr,Cc,Mm,Smin..max,Bmin..max,Nn`, e.g.
+/// `R5,C0,M1,S34..58,B34..45,NM` for Bosco's Rule:
+///
+/// * `R` is the range.
+/// * `C` is the number of states like in Generations rules, `0` means 2.
+/// * `M1` counts the cell itself as a neighbor, `M0` doesn't.
+/// * `S` and `B` are the ranges of alive neighbors for survival and birth.
+/// * `NM` is the Moore and `NN` the Von Neumann neighborhood.
+///
+/// `C`, `M` and `N` may be left out and default to `C0`, `M0` and `NM`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LargerThanLife {
+    range: u32,
+    states: u8,
+    middle: bool,
+    survival: (usize, usize),
+    birth: (usize, usize),
+    neighborhood: LtlNeighborhood,
+}
+
+impl LargerThanLife {
+    pub fn range(&self) -> u32 {
+        self.range
+    }
+
+    /// Number of states including alive and dead.
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    pub fn neighborhood(&self) -> LtlNeighborhood {
+        self.neighborhood
+    }
+
+    /// Number of cells counted as neighbors, including the cell itself if
+    /// the rule counts it.
+    pub fn size(&self) -> usize {
+        let range = self.range as usize;
+        let around = match self.neighborhood {
+            LtlNeighborhood::Moore => (2 * range + 1) * (2 * range + 1) - 1,
+            LtlNeighborhood::VonNeumann => 2 * range * (range + 1),
+        };
+        around + self.middle as usize
+    }
+
+    /// State of a cell in the next generation, where `alive_neighbors`
+    /// doesn't include the cell itself.
+    pub fn next_state(&self, state: CellState, alive_neighbors: usize) -> CellState {
+        let between = |(min, max): (usize, usize), count| min <= count && count <= max;
+        match state {
+            CellState::Dead if between(self.birth, alive_neighbors) => CellState::Alive(0),
+            CellState::Dead => CellState::Dead,
+            CellState::Alive(_)
+                if between(self.survival, alive_neighbors + self.middle as usize) =>
+            {
+                state
+            }
+            CellState::Alive(_) => decay(self.states, 0),
+            CellState::Dying(age) => decay(self.states, age),
+            // states of other families are left alone
+            CellState::Conductor | CellState::ElectronHead | CellState::ElectronTail => state,
+        }
+    }
+}
+
+pub(crate) fn parse_number(s: &str, rule: &str) -> Result<u32, RuleError> {
+    if let Some(c) = s.chars().find(|c| !c.is_ascii_digit()) {
+        return Err(RuleError::UnexpectedChar(c));
+    }
+    s.parse()
+        .map_err(|_| RuleError::Malformed(rule.to_string()))
+}
+
+/// Parses `min..max` or a single count.
+fn parse_counts(s: &str, rule: &str) -> Result<(usize, usize), RuleError> {
+    let (min, max) = match s.find("..") {
+        Some(i) => (&s[..i], &s[i + 2..]),
+        None => (s, s),
+    };
+    let min = parse_number(min, rule)? as usize;
+    let max = parse_number(max, rule)? as usize;
+    if min > max {
+        return Err(RuleError::Malformed(rule.to_string()));
+    }
+    Ok((min, max))
+}
+
+impl FromStr for LargerThanLife {
+    type Err = RuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut range = None;
+        let mut states = None;
+        let mut middle = None;
+        let mut survival = None;
+        let mut birth = None;
+        let mut neighborhood = None;
+
+        for part in s.trim().split(',') {
+            let mut chars = part.chars();
+            let letter = match chars.next() {
+                Some(c) => c.to_ascii_uppercase(),
+                None => return Err(RuleError::Malformed(s.to_string())),
+            };
+            let value = chars.as_str();
+            let duplicate = match letter {
+                'R' => range.replace(parse_number(value, s)?).is_some(),
+                'C' => states.replace(parse_number(value, s)?).is_some(),
+                'M' => {
+                    let m = match value {
+                        "0" => false,
+                        "1" => true,
+                        _ => return Err(RuleError::Malformed(s.to_string())),
+                    };
+                    middle.replace(m).is_some()
+                }
+                'S' => survival.replace(parse_counts(value, s)?).is_some(),
+                'B' => birth.replace(parse_counts(value, s)?).is_some(),
+                'N' => {
+                    let n = match value {
+                        "M" | "m" => LtlNeighborhood::Moore,
+                        "N" | "n" => LtlNeighborhood::VonNeumann,
+                        _ => return Err(RuleError::Malformed(s.to_string())),
+                    };
+                    neighborhood.replace(n).is_some()
+                }
+                c => return Err(RuleError::UnexpectedChar(c)),
+            };
+            if duplicate {
+                return Err(RuleError::Duplicate(letter));
+            }
+        }
+
+        let (range, survival, birth) = match (range, survival, birth) {
+            (Some(range), Some(survival), Some(birth)) => (range, survival, birth),
+            _ => return Err(RuleError::Malformed(s.to_string())),
+        };
+        if range == 0 || range > MAX_LTL_RANGE {
+            return Err(RuleError::RangeOutOfRange(range));
+        }
+        let states = match states.unwrap_or(0) {
+            0 | 1 => 2,
+            n => u8::try_from(n).map_err(|_| RuleError::StatesOutOfRange(n))?,
+        };
+
+        let rule = Self {
+            range,
+            states,
+            middle: middle.unwrap_or(false),
+            survival,
+            birth,
+            neighborhood: neighborhood.unwrap_or(LtlNeighborhood::Moore),
+        };
+        let max = rule.size();
+        if survival.1 > max {
+            return Err(RuleError::CountOutOfRange(survival.1, max));
+        }
+        if birth.1 > max {
+            return Err(RuleError::CountOutOfRange(birth.1, max));
+        }
+        Ok(rule)
+    }
+}
+
+impl fmt::Display for LargerThanLife {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let states = if self.states == 2 { 0 } else { self.states };
+        let neighborhood = match self.neighborhood {
+            LtlNeighborhood::Moore => 'M',
+            LtlNeighborhood::VonNeumann => 'N',
+        };
+        write!(
+            f,
+            "R{},C{},M{},S{}..{},B{}..{},N{}",
+            self.range,
+            states,
+            self.middle as u8,
+            self.survival.0,
+            self.survival.1,
+            self.birth.0,
+            self.birth.1,
+            neighborhood
+        )
+    }
+}
+
+/// Dense finite board for [`LargerThanLife`] rules.
+///
+/// Neighbors are counted with a summed-area table of the board and a margin
+/// of `range` cells resolved through the [`Topology`], so the Moore
+/// neighborhood of every cell takes four lookups regardless of the range and
+/// the Von Neumann one a lookup per row.
+#[derive(Debug, Clone)]
+pub struct LtlBoard {
+    bounds: Bounds,
+    rule: LargerThanLife,
+    topology: Topology,
+    cells: Vec<CellState>,
+    next: Vec<CellState>,
+    // alive cells in the rectangle from the corner of the margin to every
+    // cell, with an extra row and column of zeros in front
+    sums: Vec<u32>,
+    generation: u64,
+}
+
+impl LtlBoard {
+    pub fn new(bounds: Bounds, rule: LargerThanLife) -> Self {
+        let len = bounds.width() * bounds.height();
+        Self {
+            bounds,
+            rule,
+            topology: Topology::default(),
+            cells: vec![CellState::Dead; len],
+            next: vec![CellState::Dead; len],
+            sums: Vec::new(),
+            generation: 0,
+        }
+    }
+
+    pub fn with_topology(self, topology: Topology) -> Self {
+        Self { topology, ..self }
+    }
+
+    pub fn rule(&self) -> LargerThanLife {
+        self.rule
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if self.bounds.contains(x, y) {
+            let col = (x - self.bounds.min_x) as usize;
+            let row = (y - self.bounds.min_y) as usize;
+            Some(row * self.bounds.width() + col)
+        } else {
+            None
+        }
+    }
+
+    fn is_alive(&self, x: i32, y: i32) -> bool {
+        match self.topology.resolve(&self.bounds, x, y) {
+            Some((x, y)) => self.get(x, y).is_alive(),
+            None => false,
+        }
+    }
+
+    fn update_sums(&mut self) {
+        let range = self.rule.range as i32;
+        let stride = self.bounds.width() + 2 * range as usize + 1;
+        let rows = self.bounds.height() + 2 * range as usize + 1;
+        let mut sums = mem::take(&mut self.sums);
+        sums.clear();
+        sums.resize(stride * rows, 0);
+        // alive cells in front of every cell of its row, then added up
+        // along the columns
+        sums.par_chunks_mut(stride)
+            .enumerate()
+            .skip(1)
+            .for_each(|(row, sums)| {
+                let y = self.bounds.min_y - range + row as i32 - 1;
+                for col in 1..stride {
+                    let x = self.bounds.min_x - range + col as i32 - 1;
+                    sums[col] = sums[col - 1] + self.is_alive(x, y) as u32;
+                }
+            });
+        for i in stride..sums.len() {
+            sums[i] += sums[i - stride];
+        }
+        self.sums = sums;
+    }
+
+    /// Alive cells in the rectangle of the margin with the corners
+    /// `(col, row)` (inclusive) and `(end_col, end_row)` (exclusive).
+    fn sum(&self, col: usize, row: usize, end_col: usize, end_row: usize) -> u32 {
+        let stride = self.bounds.width() + 2 * self.rule.range as usize + 1;
+        self.sums[end_row * stride + end_col] + self.sums[row * stride + col]
+            - self.sums[row * stride + end_col]
+            - self.sums[end_row * stride + col]
+    }
+
+    /// Alive cells in the neighborhood of a cell, including the cell itself.
+    fn num_alive(&self, col: usize, row: usize) -> usize {
+        // the cell is at (col + range, row + range) in the margin
+        let range = self.rule.range as usize;
+        let alive = match self.rule.neighborhood {
+            LtlNeighborhood::Moore => self.sum(col, row, col + 2 * range + 1, row + 2 * range + 1),
+            LtlNeighborhood::VonNeumann => (0..=2 * range)
+                .map(|dy| {
+                    let width = range - (dy as isize - range as isize).unsigned_abs();
+                    let r = row + dy;
+                    self.sum(col + range - width, r, col + range + width + 1, r + 1)
+                })
+                .sum(),
+        };
+        alive as usize
+    }
+}
+
+impl Universe for LtlBoard {
+    fn step(&mut self) {
+        self.update_sums();
+        let mut next = mem::take(&mut self.next);
+        let width = self.bounds.width();
+        next.par_iter_mut().enumerate().for_each(|(i, next)| {
+            let state = self.cells[i];
+            let alive = self.num_alive(i % width, i / width);
+            let alive_neighbors = alive - state.is_alive() as usize;
+            *next = self.rule.next_state(state, alive_neighbors);
+        });
+        self.next = mem::replace(&mut self.cells, next);
+        self.generation += 1;
+    }
+
+    fn get(&self, x: i32, y: i32) -> CellState {
+        self.index(x, y)
+            .map(|i| self.cells[i])
+            .unwrap_or(CellState::Dead)
+    }
+
+    fn set(&mut self, x: i32, y: i32, state: CellState) {
+        if let Some(i) = self.index(x, y) {
+            self.cells[i] = state;
+        }
+    }
+
+    fn population(&self) -> usize {
+        self.cells.iter().filter(|c| **c != CellState::Dead).count()
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Some(self.bounds)
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+}
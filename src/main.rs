@@ -12,6 +12,9 @@ use amethyst::ui::RenderUi;
 use amethyst::ui::UiBundle;
 use amethyst::utils::fps_counter::FpsCounterBundle;
 use amethyst::utils::ortho_camera::CameraOrthoSystem;
+use log::{error, info};
+
+use game_of_live::{plugin_paths, PluginRule};
 
 mod states;
 mod systems;
@@ -38,6 +41,18 @@ fn main() -> amethyst::Result<()> {
 
     let initial_state = LoadingState::default();
 
+    let mut cell_bundle = CellBundle::default();
+    for path in plugin_paths(&app_root.join("plugins"))? {
+        // plugins are trusted like the game itself, they are built in-house
+        let loaded = unsafe { PluginRule::load(&path) }
+            .map_err(|e| e.to_string())
+            .and_then(|rule| cell_bundle.register(rule).map_err(|e| e.to_string()));
+        match loaded {
+            Ok(rule) => info!("Loaded rule {:?} from {}", rule.name(), path.display()),
+            Err(e) => error!("Failed to load plugin {}: {}", path.display(), e),
+        }
+    }
+
     let board_config = BoardConfig::load(&board_config)?;
 
     let input_bundle =
@@ -59,7 +74,7 @@ fn main() -> amethyst::Result<()> {
         .with_bundle(ControlBundle::new(configs_dir))?
         .with_bundle(FpsCounterBundle)?
         .with_bundle(FpsDisplayBundle)?
-        .with_bundle(CellBundle)?
+        .with_bundle(cell_bundle)?
         .with_bundle(DragWorldBundle)?;
 
     let mut game = Application::build(assets_dir, initial_state)?
@@ -0,0 +1,281 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::mem;
+use std::str::FromStr;
+
+use crate::{Bounds, CellState, Edges, RuleError, Topology, Universe};
+
+/// Block rule on the Margolus neighborhood.
+///
+/// The board is split into blocks of 2x2 cells, and every step each block is
+/// replaced as a whole. The blocks are shifted by one cell diagonally every
+/// other step so information travels between them.
+///
+/// Written in MCell's notation `MS,D<table>` with 16 numbers separated by
+/// `;`, where entry `n` is the block that replaces block `n`. The cells of a
+/// block are the bits 1 (upper left), 2 (upper right), 4 (lower left) and 8
+/// (lower right), where upper is towards positive y. `Critters`, `Tron` and
+/// `BBM` (billiard-ball machine) are accepted by name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Margolus {
+    table: [u8; 16],
+}
+
+impl Margolus {
+    const PRESETS: [(&'static str, [u8; 16]); 3] = [
+        (
+            "Critters",
+            [15, 14, 13, 3, 11, 5, 6, 1, 7, 9, 10, 2, 12, 4, 8, 0],
+        ),
+        (
+            "Tron",
+            [15, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 0],
+        ),
+        (
+            "BBM",
+            [0, 8, 4, 3, 2, 5, 9, 7, 1, 6, 10, 11, 12, 13, 14, 15],
+        ),
+    ];
+
+    /// Fails if an entry isn't a block, i.e. larger than 15.
+    pub fn new(table: [u8; 16]) -> Result<Self, RuleError> {
+        let rule = Self { table };
+        if table.iter().any(|&entry| entry > 15) {
+            return Err(RuleError::Malformed(rule.to_string()));
+        }
+        Ok(rule)
+    }
+
+    pub fn table(&self) -> [u8; 16] {
+        self.table
+    }
+
+    /// Block that replaces `block`.
+    pub fn next_block(&self, block: u8) -> u8 {
+        self.table[block as usize & 15]
+    }
+
+    /// Whether every block is replaced by a different one, so every step can
+    /// be undone.
+    pub fn is_reversible(&self) -> bool {
+        let mut seen = [false; 16];
+        self.table
+            .iter()
+            .all(|&block| !mem::replace(&mut seen[block as usize], true))
+    }
+
+    /// Rule undoing every step of this one, if it is reversible.
+    pub fn inverse(&self) -> Option<Self> {
+        if !self.is_reversible() {
+            return None;
+        }
+        let mut table = [0; 16];
+        for (block, &next) in self.table.iter().enumerate() {
+            table[next as usize] = block as u8;
+        }
+        Some(Self { table })
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        Self::PRESETS
+            .iter()
+            .find(|(_, table)| *table == self.table)
+            .map(|&(name, _)| name)
+    }
+}
+
+impl FromStr for Margolus {
+    type Err = RuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let preset = Self::PRESETS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s));
+        if let Some(&(_, table)) = preset {
+            return Ok(Self { table });
+        }
+
+        let malformed = || RuleError::Malformed(s.to_string());
+        let upper = s.to_ascii_uppercase();
+        let entries = upper.strip_prefix("MS,D").ok_or_else(malformed)?;
+        let entries = entries
+            .split(';')
+            .map(|entry| {
+                if let Some(c) = entry.chars().find(|c| !c.is_ascii_digit()) {
+                    return Err(RuleError::UnexpectedChar(c));
+                }
+                entry.parse::<u8>().map_err(|_| malformed())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let table = <[u8; 16]>::try_from(&entries[..]).map_err(|_| malformed())?;
+        Self::new(table)
+    }
+}
+
+impl fmt::Display for Margolus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = self.name() {
+            return write!(f, "{}", name);
+        }
+        let entries: Vec<String> = self.table.iter().map(|n| n.to_string()).collect();
+        write!(f, "MS,D{}", entries.join(";"))
+    }
+}
+
+/// Dense finite board for [`Margolus`] rules.
+///
+/// Blocks start at `(min_x, min_y)` in even steps and one cell further along
+/// both axes in odd ones. Cells of a block outside of the bounds are
+/// resolved through the [`Topology`], or are dead and stay dead if there is
+/// no such cell. Axes that wrap around need an even length so every cell is
+/// in exactly one block, see [`MargolusBoard::with_topology`].
+///
+/// [`Universe::reverse`] runs reversible rules backwards with the
+/// [`Margolus::inverse`] rule on a [`Topology::Torus`]. Blocks crossing
+/// closed edges lose the cells outside, and blocks crossing twisted edges
+/// are mirrored, so no other topology can be undone.
+#[derive(Debug, Clone)]
+pub struct MargolusBoard {
+    bounds: Bounds,
+    rule: Margolus,
+    topology: Topology,
+    cells: Vec<CellState>,
+    next: Vec<CellState>,
+    // whether the blocks of the next forward step are shifted
+    shifted: bool,
+    reversed: bool,
+    generation: u64,
+}
+
+impl MargolusBoard {
+    pub fn new(bounds: Bounds, rule: Margolus) -> Self {
+        let len = bounds.width() * bounds.height();
+        Self {
+            bounds,
+            rule,
+            topology: Topology::default(),
+            cells: vec![CellState::Dead; len],
+            next: vec![CellState::Dead; len],
+            shifted: false,
+            reversed: false,
+            generation: 0,
+        }
+    }
+
+    /// Fails if an axis that isn't closed has an odd length, or the other
+    /// axis of a twisted one, since the blocks wouldn't line up across the
+    /// edges.
+    pub fn with_topology(self, topology: Topology) -> Result<Self, RuleError> {
+        let (width, height) = (self.bounds.width(), self.bounds.height());
+        let (x_edges, y_edges) = topology.edges();
+        let odd = |edges: Edges, length: usize, other: usize| match edges {
+            Edges::Closed => false,
+            Edges::Joined => length % 2 == 1,
+            Edges::Twisted => length % 2 == 1 || other % 2 == 1,
+        };
+        if odd(x_edges, width, height) || odd(y_edges, height, width) {
+            return Err(RuleError::Unsupported(format!(
+                "Margolus rules need an even width and height on a {:?} board",
+                topology
+            )));
+        }
+        Ok(Self { topology, ..self })
+    }
+
+    pub fn rule(&self) -> Margolus {
+        self.rule
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if self.bounds.contains(x, y) {
+            let col = (x - self.bounds.min_x) as usize;
+            let row = (y - self.bounds.min_y) as usize;
+            Some(row * self.bounds.width() + col)
+        } else {
+            None
+        }
+    }
+}
+
+impl Universe for MargolusBoard {
+    fn step(&mut self) {
+        let rule = if self.reversed {
+            // undoes the previous forward step, which used the other offset
+            self.shifted = !self.shifted;
+            self.rule
+                .inverse()
+                .expect("only reversible rules run backwards")
+        } else {
+            self.rule
+        };
+        let mut next = mem::take(&mut self.next);
+        next.copy_from_slice(&self.cells);
+        let shift = self.shifted as i32;
+        for y in (self.bounds.min_y - shift..=self.bounds.max_y).step_by(2) {
+            for x in (self.bounds.min_x - shift..=self.bounds.max_x).step_by(2) {
+                // in the order of the bits of the block
+                let cells = [(x, y + 1), (x + 1, y + 1), (x, y), (x + 1, y)].map(|(x, y)| {
+                    self.topology
+                        .resolve(&self.bounds, x, y)
+                        .and_then(|(x, y)| self.index(x, y))
+                });
+                let block = cells.iter().enumerate().fold(0, |block, (bit, &i)| {
+                    let alive = i.is_some_and(|i| self.cells[i] != CellState::Dead);
+                    block | (alive as u8) << bit
+                });
+                let block = rule.next_block(block);
+                for (bit, &i) in cells.iter().enumerate() {
+                    if let Some(i) = i {
+                        next[i] = if block & 1 << bit != 0 {
+                            CellState::Alive(0)
+                        } else {
+                            CellState::Dead
+                        };
+                    }
+                }
+            }
+        }
+        self.next = mem::replace(&mut self.cells, next);
+        if !self.reversed {
+            self.shifted = !self.shifted;
+        }
+        self.generation += 1;
+    }
+
+    fn reverse(&mut self) -> bool {
+        if !self.rule.is_reversible() || self.topology != Topology::Torus {
+            return false;
+        }
+        self.reversed = !self.reversed;
+        true
+    }
+
+    fn get(&self, x: i32, y: i32) -> CellState {
+        self.index(x, y)
+            .map(|i| self.cells[i])
+            .unwrap_or(CellState::Dead)
+    }
+
+    fn set(&mut self, x: i32, y: i32, state: CellState) {
+        if let Some(i) = self.index(x, y) {
+            self.cells[i] = state;
+        }
+    }
+
+    fn population(&self) -> usize {
+        self.cells.iter().filter(|c| **c != CellState::Dead).count()
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Some(self.bounds)
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+}
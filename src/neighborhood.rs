@@ -0,0 +1,223 @@
+use std::slice;
+
+use crate::RuleError;
+
+/// Largest distance along each axis of a neighbor in a custom neighborhood.
+pub const MAX_RANGE: i32 = 3;
+
+// custom neighborhoods are stored as bits of a square around the cell
+const MASK_WIDTH: i32 = 2 * MAX_RANGE + 1;
+
+/// Tiling of the plane that the cells are laid out on.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Grid {
+    #[default]
+    Square,
+    /// Hexagons in offset coordinates, where odd rows are shifted half a cell
+    /// towards positive x.
+    Hexagonal,
+    /// Triangles in alternating orientation, where the cell at `(x, y)` points
+    /// towards positive y if `x + y` is even and its base is shared with the
+    /// cell at `(x, y - 1)`.
+    Triangular,
+}
+
+impl Grid {
+    /// Whether the cell at `(x, y)` points towards positive y on a
+    /// triangular grid.
+    pub fn points_up(self, x: i32, y: i32) -> bool {
+        self == Grid::Triangular && (x + y).rem_euclid(2) == 0
+    }
+}
+
+/// Cells that count as neighbors of a cell.
+///
+/// All neighborhoods except custom ones are symmetric: if a cell is a
+/// neighbor of another cell, the other cell is also its neighbor.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Neighborhood {
+    /// The 8 cells around a cell on a square grid.
+    #[default]
+    Moore,
+    /// The 4 cells sharing an edge with a cell on a square grid.
+    VonNeumann,
+    /// The 6 cells around a cell on a hexagonal grid.
+    Hexagonal,
+    /// The 3 cells sharing an edge with a cell on a triangular grid.
+    TriangularEdges,
+    /// The 12 cells sharing an edge or a corner with a cell on a triangular
+    /// grid.
+    Triangular,
+    /// Any cells on a square grid at most [`MAX_RANGE`] cells away along each
+    /// axis, see [`Neighborhood::custom`].
+    Custom(Mask),
+}
+
+/// Offsets of a custom [`Neighborhood`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Mask {
+    // bit (dy + MAX_RANGE) * MASK_WIDTH + dx + MAX_RANGE is set for every
+    // offset (dx, dy)
+    bits: u64,
+}
+
+/// Iterator over the offsets of a [`Neighborhood`].
+#[derive(Debug, Clone)]
+pub struct Offsets {
+    fixed: slice::Iter<'static, (i32, i32)>,
+    mask: u64,
+}
+
+impl Iterator for Offsets {
+    type Item = (i32, i32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(&offset) = self.fixed.next() {
+            return Some(offset);
+        }
+        if self.mask == 0 {
+            return None;
+        }
+        let bit = self.mask.trailing_zeros() as i32;
+        self.mask &= self.mask - 1;
+        Some((bit % MASK_WIDTH - MAX_RANGE, bit / MASK_WIDTH - MAX_RANGE))
+    }
+}
+
+const MOORE: [(i32, i32); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+const VON_NEUMANN: [(i32, i32); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+const HEXAGONAL_EVEN_ROW: [(i32, i32); 6] = [(-1, -1), (0, -1), (-1, 0), (1, 0), (-1, 1), (0, 1)];
+
+const HEXAGONAL_ODD_ROW: [(i32, i32); 6] = [(0, -1), (1, -1), (-1, 0), (1, 0), (0, 1), (1, 1)];
+
+const TRIANGULAR_EDGES_UP: [(i32, i32); 3] = [(-1, 0), (1, 0), (0, -1)];
+
+const TRIANGULAR_EDGES_DOWN: [(i32, i32); 3] = [(-1, 0), (1, 0), (0, 1)];
+
+// the row on the side of the base is two cells wider than the one on the side
+// of the tip
+const TRIANGULAR_UP: [(i32, i32); 12] = [
+    (-2, -1),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (2, -1),
+    (-2, 0),
+    (-1, 0),
+    (1, 0),
+    (2, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+const TRIANGULAR_DOWN: [(i32, i32); 12] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-2, 0),
+    (-1, 0),
+    (1, 0),
+    (2, 0),
+    (-2, 1),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (2, 1),
+];
+
+impl Neighborhood {
+    /// Neighborhood of the cells at the given offsets from a cell.
+    ///
+    /// The offsets may include `(0, 0)` to count the cell itself.
+    pub fn custom(offsets: &[(i32, i32)]) -> Result<Self, RuleError> {
+        let mut bits = 0;
+        for &(dx, dy) in offsets {
+            if dx.abs() > MAX_RANGE || dy.abs() > MAX_RANGE {
+                return Err(RuleError::NeighborOutOfRange(dx, dy));
+            }
+            bits |= 1 << ((dy + MAX_RANGE) * MASK_WIDTH + dx + MAX_RANGE);
+        }
+        Ok(Neighborhood::Custom(Mask { bits }))
+    }
+
+    /// Number of neighbors of every cell.
+    pub fn size(self) -> usize {
+        match self {
+            Neighborhood::Moore => MOORE.len(),
+            Neighborhood::VonNeumann => VON_NEUMANN.len(),
+            Neighborhood::Custom(mask) => mask.bits.count_ones() as usize,
+            Neighborhood::Hexagonal => HEXAGONAL_EVEN_ROW.len(),
+            Neighborhood::TriangularEdges => TRIANGULAR_EDGES_UP.len(),
+            Neighborhood::Triangular => TRIANGULAR_UP.len(),
+        }
+    }
+
+    pub fn grid(self) -> Grid {
+        match self {
+            Neighborhood::Moore | Neighborhood::VonNeumann | Neighborhood::Custom(_) => {
+                Grid::Square
+            }
+            Neighborhood::Hexagonal => Grid::Hexagonal,
+            Neighborhood::TriangularEdges | Neighborhood::Triangular => Grid::Triangular,
+        }
+    }
+
+    /// Offsets from the cell at `(x, y)` to its neighbors.
+    ///
+    /// They depend on the position of the cell on grids where not all cells
+    /// have the same shape or alignment.
+    pub fn offsets(self, x: i32, y: i32) -> Offsets {
+        let even_row = y.rem_euclid(2) == 0;
+        let points_up = Grid::Triangular.points_up(x, y);
+        let fixed: &'static [(i32, i32)] = match self {
+            Neighborhood::Moore => &MOORE,
+            Neighborhood::VonNeumann => &VON_NEUMANN,
+            Neighborhood::Custom(mask) => {
+                return Offsets {
+                    fixed: [].iter(),
+                    mask: mask.bits,
+                }
+            }
+            Neighborhood::Hexagonal if even_row => &HEXAGONAL_EVEN_ROW,
+            Neighborhood::Hexagonal => &HEXAGONAL_ODD_ROW,
+            Neighborhood::TriangularEdges if points_up => &TRIANGULAR_EDGES_UP,
+            Neighborhood::TriangularEdges => &TRIANGULAR_EDGES_DOWN,
+            Neighborhood::Triangular if points_up => &TRIANGULAR_UP,
+            Neighborhood::Triangular => &TRIANGULAR_DOWN,
+        };
+        Offsets {
+            fixed: fixed.iter(),
+            mask: 0,
+        }
+    }
+
+    /// Neighborhood of the cells that have a cell as their neighbor.
+    ///
+    /// Only differs from this one for custom neighborhoods that aren't
+    /// symmetric.
+    pub fn mirrored(self) -> Self {
+        match self {
+            Neighborhood::Custom(mask) => {
+                // the offset (dx, dy) moves to (-dx, -dy) which reverses the
+                // order of the bits in the square
+                let square = (MASK_WIDTH * MASK_WIDTH) as u32;
+                Neighborhood::Custom(Mask {
+                    bits: mask.bits.reverse_bits() >> (64 - square),
+                })
+            }
+            _ => self,
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use std::mem;
+
+use crate::bits::BitRule;
+use crate::{Bounds, CellState, Neighborhood, Rule, RuleError, Topology, Universe};
+
+/// Dense finite board storing 64 cells per word.
+///
+/// Neighbors of all 64 cells of a word are counted at once with bitwise
+/// adders, which makes this much faster and smaller than [`crate::Board`]
+/// for boards of millions of cells.
+///
+/// Only supports totalistic two-state rules with one color.
+#[derive(Debug, Clone)]
+pub struct PackedBoard {
+    bounds: Bounds,
+    rule: Rule,
+    bit_rule: BitRule,
+    topology: Topology,
+    words_per_row: usize,
+    // neighbors of the rows next to closed edges
+    empty_row: Vec<u64>,
+    // bit i of word k of a row is the cell at x = min_x + 64 * k + i
+    words: Vec<u64>,
+    next: Vec<u64>,
+    generation: u64,
+}
+
+/// Where the cells of a row next to the board come from.
+#[derive(Debug, Clone, Copy)]
+enum RowSource {
+    Empty,
+    Row(usize),
+    /// Row mirrored along the x axis.
+    Reversed(usize),
+}
+
+impl PackedBoard {
+    pub fn new(bounds: Bounds, rule: Rule) -> Result<Self, RuleError> {
+        if rule.states() > 2 {
+            return Err(RuleError::Unsupported(
+                "the packed engine only supports rules with two states".to_string(),
+            ));
+        }
+        if rule.colors() > 1 {
+            return Err(RuleError::Unsupported(
+                "the packed engine only supports rules with one color".to_string(),
+            ));
+        }
+        if rule.neighborhood() != Neighborhood::Moore {
+            return Err(RuleError::Unsupported(
+                "the packed engine only supports the Moore neighborhood".to_string(),
+            ));
+        }
+        if !rule.is_totalistic() {
+            return Err(RuleError::Unsupported(
+                "the packed engine only supports totalistic rules".to_string(),
+            ));
+        }
+        let words_per_row = bounds.width().div_ceil(64);
+        let len = words_per_row * bounds.height();
+        Ok(Self {
+            bounds,
+            rule,
+            bit_rule: BitRule::new(&rule),
+            topology: Topology::default(),
+            words_per_row,
+            empty_row: vec![0; words_per_row],
+            words: vec![0; len],
+            next: vec![0; len],
+            generation: 0,
+        })
+    }
+
+    pub fn with_topology(self, topology: Topology) -> Self {
+        Self { topology, ..self }
+    }
+
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    fn row(&self, row: usize) -> &[u64] {
+        &self.words[row * self.words_per_row..(row + 1) * self.words_per_row]
+    }
+
+    /// Mask of the bits of the last word of a row that are on the board.
+    fn last_word_mask(&self) -> u64 {
+        match self.bounds.width() % 64 {
+            0 => !0,
+            bits => (1 << bits) - 1,
+        }
+    }
+
+    /// Word index and bit of a cell on the board.
+    fn position(&self, x: i32, y: i32) -> Option<(usize, u32)> {
+        if self.bounds.contains(x, y) {
+            let col = (x - self.bounds.min_x) as usize;
+            let row = (y - self.bounds.min_y) as usize;
+            Some((row * self.words_per_row + col / 64, (col % 64) as u32))
+        } else {
+            None
+        }
+    }
+
+    fn is_alive(&self, x: i32, y: i32) -> bool {
+        match self.topology.resolve(&self.bounds, x, y) {
+            Some((x, y)) => self.get(x, y).is_alive(),
+            None => false,
+        }
+    }
+
+    /// Where the row at `y` (possibly outside of the board) comes from.
+    fn row_source(&self, y: i32) -> RowSource {
+        let b = &self.bounds;
+        let first = self.topology.resolve(b, b.min_x, y);
+        let last = self.topology.resolve(b, b.max_x, y);
+        match (first, last) {
+            (Some((first_x, row)), Some((last_x, _))) => {
+                let row = (row - b.min_y) as usize;
+                if first_x <= last_x {
+                    RowSource::Row(row)
+                } else {
+                    RowSource::Reversed(row)
+                }
+            }
+            _ => RowSource::Empty,
+        }
+    }
+
+    fn source_row<'a>(&'a self, source: RowSource, reversed: &'a Option<Vec<u64>>) -> &'a [u64] {
+        match (source, reversed) {
+            (_, Some(reversed)) => reversed,
+            (RowSource::Row(row), None) => self.row(row),
+            _ => &self.empty_row,
+        }
+    }
+
+    fn reversed_row(&self, source: RowSource) -> Option<Vec<u64>> {
+        let row = match source {
+            RowSource::Reversed(row) => row,
+            _ => return None,
+        };
+        let width = self.bounds.width();
+        let mut reversed = vec![0; self.words_per_row];
+        let words = self.row(row);
+        for col in 0..width {
+            if words[col / 64] & (1 << (col % 64)) != 0 {
+                let to = width - 1 - col;
+                reversed[to / 64] |= 1 << (to % 64);
+            }
+        }
+        Some(reversed)
+    }
+}
+
+/// Cells of a row shifted so that every bit holds its west (east) neighbor,
+/// with `halo` as the neighbor of the cell at the edge.
+fn shift_west(row: &[u64], k: usize, halo: bool) -> u64 {
+    let carry = if k == 0 {
+        halo as u64
+    } else {
+        row[k - 1] >> 63
+    };
+    row[k] << 1 | carry
+}
+
+fn shift_east(row: &[u64], k: usize, halo: bool, width: usize) -> u64 {
+    let mut shifted = row[k] >> 1;
+    if k + 1 < row.len() {
+        shifted |= row[k + 1] << 63;
+    } else if halo {
+        shifted |= 1 << ((width - 1) % 64);
+    }
+    shifted
+}
+
+impl Universe for PackedBoard {
+    fn step(&mut self) {
+        let b = self.bounds;
+        let width = b.width();
+        let last_word_mask = self.last_word_mask();
+
+        let mut next = mem::take(&mut self.next);
+        for row in 0..b.height() {
+            let y = b.min_y + row as i32;
+
+            let above = self.row_source(y - 1);
+            let below = self.row_source(y + 1);
+            // reversed rows only happen at twisted edges
+            let reversed_above = self.reversed_row(above);
+            let reversed_below = self.reversed_row(below);
+            let rows = [
+                self.source_row(above, &reversed_above),
+                self.row(row),
+                self.source_row(below, &reversed_below),
+            ];
+            let halo = |dy| {
+                (
+                    self.is_alive(b.min_x - 1, y + dy),
+                    self.is_alive(b.max_x + 1, y + dy),
+                )
+            };
+            let halos = [halo(-1), halo(0), halo(1)];
+
+            for k in 0..self.words_per_row {
+                let (above, center, below) = (rows[0], rows[1], rows[2]);
+                let neighbors = [
+                    shift_west(above, k, halos[0].0),
+                    above[k],
+                    shift_east(above, k, halos[0].1, width),
+                    shift_west(center, k, halos[1].0),
+                    shift_east(center, k, halos[1].1, width),
+                    shift_west(below, k, halos[2].0),
+                    below[k],
+                    shift_east(below, k, halos[2].1, width),
+                ];
+                let mut word = self.bit_rule.next(center[k], neighbors);
+                if k + 1 == self.words_per_row {
+                    word &= last_word_mask;
+                }
+                next[row * self.words_per_row + k] = word;
+            }
+        }
+
+        self.next = mem::replace(&mut self.words, next);
+        self.generation += 1;
+    }
+
+    fn get(&self, x: i32, y: i32) -> CellState {
+        match self.position(x, y) {
+            Some((word, bit)) if self.words[word] & (1 << bit) != 0 => CellState::Alive(0),
+            _ => CellState::Dead,
+        }
+    }
+
+    fn set(&mut self, x: i32, y: i32, state: CellState) {
+        if let Some((word, bit)) = self.position(x, y) {
+            if state == CellState::Dead {
+                self.words[word] &= !(1 << bit);
+            } else {
+                self.words[word] |= 1 << bit;
+            }
+        }
+    }
+
+    fn population(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Some(self.bounds)
+    }
+
+    fn live_cells(&self, area: &Bounds) -> Vec<(i32, i32, CellState)> {
+        let b = &self.bounds;
+        let mut cells = Vec::new();
+        for y in area.min_y.max(b.min_y)..=area.max_y.min(b.max_y) {
+            let row = self.row((y - b.min_y) as usize);
+            for (k, &word) in row.iter().enumerate() {
+                let mut word = word;
+                // skips empty words without looking at every cell
+                while word != 0 {
+                    let bit = word.trailing_zeros();
+                    word &= word - 1;
+                    let x = b.min_x + (k * 64) as i32 + bit as i32;
+                    if x >= area.min_x && x <= area.max_x {
+                        cells.push((x, y, CellState::Alive(0)));
+                    }
+                }
+            }
+        }
+        cells
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+}
@@ -0,0 +1,220 @@
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::os::raw::c_char;
+use std::path::{Path, PathBuf};
+
+use libloading::{Library, Symbol};
+
+use crate::{CellRule, Neighborhood, Shading};
+
+/// Version of the plugin API, plugins built against another version are
+/// rejected.
+pub const PLUGIN_API_VERSION: u32 = 1;
+
+/// Symbol of the function returning the [`PLUGIN_API_VERSION`] a plugin was
+/// built against, `extern "C" fn() -> u32`.
+pub const PLUGIN_VERSION_SYMBOL: &[u8] = b"game_of_live_plugin_version\0";
+
+/// Symbol of the function returning the [`PluginInfo`] of a plugin,
+/// `extern "C" fn() -> PluginInfo`.
+pub const PLUGIN_INFO_SYMBOL: &[u8] = b"game_of_live_plugin_info\0";
+
+/// Rule of a plugin as seen through the C ABI.
+///
+/// A plugin is a `cdylib` exporting both symbols, e.g. Seeds:
+///
+/// ```
+/// use std::os::raw::c_char;
+/// use std::slice;
+///
+/// use game_of_live::{PluginInfo, PLUGIN_API_VERSION};
+///
+/// #[no_mangle]
+/// pub extern "C" fn game_of_live_plugin_version() -> u32 {
+///     PLUGIN_API_VERSION
+/// }
+///
+/// #[no_mangle]
+/// pub extern "C" fn game_of_live_plugin_info() -> PluginInfo {
+///     PluginInfo {
+///         name: b"Seeds\0".as_ptr() as *const c_char,
+///         states: 2,
+///         neighborhood: PluginInfo::MOORE,
+///         palette: false,
+///         next_state: Some(next_state),
+///     }
+/// }
+///
+/// extern "C" fn next_state(state: u8, neighbors: *const u8, len: usize) -> u8 {
+///     let neighbors = unsafe { slice::from_raw_parts(neighbors, len) };
+///     let alive = neighbors.iter().filter(|&&neighbor| neighbor == 1).count();
+///     (state == 0 && alive == 2) as u8
+/// }
+/// ```
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PluginInfo {
+    /// Nul-terminated name that lives as long as the library is loaded.
+    pub name: *const c_char,
+    /// Number of states including dead, see [`CellRule::states`].
+    pub states: u8,
+    /// One of the neighborhood constants like [`PluginInfo::MOORE`].
+    pub neighborhood: u8,
+    /// Gives every state but dead a color of its own, see
+    /// [`Shading::Palette`].
+    pub palette: bool,
+    /// Computes the state of a cell in the next generation from its state
+    /// and the `len` states of its neighbors, see [`CellRule::next_state`].
+    pub next_state: Option<extern "C" fn(state: u8, neighbors: *const u8, len: usize) -> u8>,
+}
+
+impl PluginInfo {
+    pub const MOORE: u8 = 0;
+    pub const VON_NEUMANN: u8 = 1;
+    pub const HEXAGONAL: u8 = 2;
+    pub const TRIANGULAR_EDGES: u8 = 3;
+    pub const TRIANGULAR: u8 = 4;
+}
+
+/// Error when loading a plugin.
+#[derive(Debug)]
+pub enum PluginError {
+    /// The library can't be loaded or doesn't export both symbols.
+    Load(io::Error),
+    /// The plugin was built against this version of the API instead of
+    /// [`PLUGIN_API_VERSION`].
+    Version(u32),
+    /// The [`PluginInfo`] of the plugin is invalid.
+    Invalid(&'static str),
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PluginError::Load(e) => write!(f, "can't load plugin: {}", e),
+            PluginError::Version(version) => write!(
+                f,
+                "plugin API version {} is not supported, expected {}",
+                version, PLUGIN_API_VERSION
+            ),
+            PluginError::Invalid(reason) => write!(f, "invalid plugin: {}", reason),
+        }
+    }
+}
+
+impl Error for PluginError {}
+
+/// [`CellRule`] of a plugin loaded from a shared library.
+#[derive(Debug)]
+pub struct PluginRule {
+    name: String,
+    states: u8,
+    neighborhood: Neighborhood,
+    shading: Shading,
+    next_state: extern "C" fn(u8, *const u8, usize) -> u8,
+    // keeps `next_state` loaded
+    _library: Library,
+}
+
+impl PluginRule {
+    /// Loads the plugin in the shared library at `path`.
+    ///
+    /// # Safety
+    ///
+    /// Loading a library runs its initialization code and the plugin has to
+    /// uphold the contract of [`PluginInfo`], so only trusted libraries may
+    /// be loaded.
+    pub unsafe fn load(path: &Path) -> Result<Self, PluginError> {
+        let library = Library::new(path).map_err(PluginError::Load)?;
+        let version: Symbol<extern "C" fn() -> u32> = library
+            .get(PLUGIN_VERSION_SYMBOL)
+            .map_err(PluginError::Load)?;
+        let version = version();
+        if version != PLUGIN_API_VERSION {
+            return Err(PluginError::Version(version));
+        }
+        let info: Symbol<extern "C" fn() -> PluginInfo> =
+            library.get(PLUGIN_INFO_SYMBOL).map_err(PluginError::Load)?;
+        let info = info();
+
+        if info.name.is_null() {
+            return Err(PluginError::Invalid("the name is missing"));
+        }
+        let name = match CStr::from_ptr(info.name).to_str() {
+            Ok(name) if !name.trim().is_empty() => name.to_string(),
+            _ => return Err(PluginError::Invalid("the name is empty or not UTF-8")),
+        };
+        let neighborhood = match info.neighborhood {
+            PluginInfo::MOORE => Neighborhood::Moore,
+            PluginInfo::VON_NEUMANN => Neighborhood::VonNeumann,
+            PluginInfo::HEXAGONAL => Neighborhood::Hexagonal,
+            PluginInfo::TRIANGULAR_EDGES => Neighborhood::TriangularEdges,
+            PluginInfo::TRIANGULAR => Neighborhood::Triangular,
+            _ => return Err(PluginError::Invalid("unknown neighborhood")),
+        };
+        let shading = if info.palette {
+            Shading::Palette
+        } else {
+            Shading::Fade
+        };
+        let next_state = info
+            .next_state
+            .ok_or(PluginError::Invalid("the next state function is missing"))?;
+
+        Ok(Self {
+            name,
+            states: info.states,
+            neighborhood,
+            shading,
+            next_state,
+            _library: library,
+        })
+    }
+}
+
+impl CellRule for PluginRule {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn states(&self) -> u8 {
+        self.states
+    }
+
+    fn neighborhood(&self) -> Neighborhood {
+        self.neighborhood
+    }
+
+    fn next_state(&self, state: u8, neighbors: &[u8]) -> u8 {
+        (self.next_state)(state, neighbors.as_ptr(), neighbors.len())
+    }
+
+    fn shading(&self) -> Shading {
+        self.shading
+    }
+}
+
+/// Shared libraries in `dir` in alphabetical order, a missing directory
+/// has none.
+pub fn plugin_paths(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        let is_library = path
+            .extension()
+            .is_some_and(|extension| extension == std::env::consts::DLL_EXTENSION);
+        if is_library && path.is_file() {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
@@ -0,0 +1,29 @@
+//! Seeded random numbers for stochastic rules and random starts.
+
+/// SplitMix64 generator, which gives the same numbers for the same seed on
+/// every platform so runs can be reproduced.
+#[derive(Debug, Clone)]
+pub(crate) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// `true` with the given probability.
+    pub(crate) fn chance(&mut self, probability: f32) -> bool {
+        // 24 bits are exactly representable, so this is below 1
+        let sample = (self.next_u64() >> 40) as f32 / (1 << 24) as f32;
+        sample < probability
+    }
+}
@@ -0,0 +1,591 @@
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::hensel::{self, Configurations, Hensel};
+use crate::ltl::parse_number;
+use crate::{CellState, Neighborhood, MAX_LTL_RANGE, MAX_RANGE};
+
+/// Largest number of alive neighbors a cell can have in any neighborhood,
+/// reached by a custom neighborhood covering the whole square of
+/// [`MAX_RANGE`] around and including the cell.
+pub const MAX_NEIGHBORS: u8 = 49;
+
+// larger counts are written as a comma separated list, for rules without a
+// neighborhood letter the digits stop at 8 since letters are Hensel notation
+const MAX_DIGIT: u8 = 12;
+
+/// Most alive neighbors in the Moore neighborhood.
+const MAX_MOORE_DIGIT: u8 = 8;
+
+/// Most states a Generations rule can have.
+pub const MAX_STATES: u8 = 255;
+
+/// Most colors alive cells of a multi-color rule can have.
+pub const MAX_COLORS: u8 = 8;
+
+/// Life-like rule in B/S notation, e.g. `B3/S23` for Conway's Game of Life.
+///
+/// A dead cell is born if its number of alive neighbors is listed after the
+/// `B` and an alive cell survives if the number is listed after the `S`.
+///
+/// Generations rules add the number of states with a `C`, e.g. `B2/S/C3`
+/// for Brian's Brain. Alive cells that don't survive then pass through
+/// `states - 2` dying states before they are dead. Dying cells don't count
+/// as alive neighbors.
+///
+/// Multi-color rules add the number of colors of alive cells with a `K`,
+/// e.g. `B3/S23/K2` for Immigration or `B3/S23/K4` for QuadLife. A cell that
+/// is born takes the color of the majority of its alive neighbors, see
+/// [`Rule::birth_color`], and surviving cells keep their color.
+///
+/// Rules with another neighborhood end with its letter, e.g. `B2/S34H` for a
+/// hexagonal rule or `B1/S1V` for a Von Neumann rule. Triangular rules end
+/// with `L` for 12 or `LE` for 3 neighbors. Counts from 10 to 12 are written
+/// as `a` to `c`, rules with larger counts list them separated by commas like
+/// `B3,14/S2,3,13`.
+///
+/// Moore rules may be isotropic non-totalistic in Hensel notation, where the
+/// letters after a count pick which arrangements of that many alive
+/// neighbors are meant, e.g. `B2ce3aiy/S23` or `B2-a/S12` for all but `2a`.
+/// Such rules look up the exact configuration of the neighbors instead of
+/// just counting them, see [`Rule::is_totalistic`].
+///
+/// Rules without a letter use the Moore neighborhood, so their digits are
+/// counts from 0 to 8. Counts up to [`MAX_NEIGHBORS`] can only be listed
+/// separated by commas like `B3,14/S2,3` or `B9,/S` for a single one, so
+/// they can be combined with a larger custom neighborhood by
+/// [`Rule::with_neighborhood`]. Custom neighborhoods have no letter and are
+/// lost when the rule is turned into a string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Rule {
+    // bit n is set if n alive neighbors cause a birth (survival)
+    birth: u64,
+    survival: u64,
+    states: u8,
+    colors: u8,
+    neighborhood: Neighborhood,
+    hensel: Option<Hensel>,
+}
+
+impl Rule {
+    /// Creates a rule from the neighbor counts that cause births and
+    /// survivals.
+    pub fn new(birth: &[u8], survival: &[u8]) -> Result<Self, RuleError> {
+        Ok(Self {
+            birth: counts_to_mask(birth, MAX_NEIGHBORS.into())?,
+            survival: counts_to_mask(survival, MAX_NEIGHBORS.into())?,
+            states: 2,
+            colors: 1,
+            neighborhood: Neighborhood::Moore,
+            hensel: None,
+        })
+    }
+
+    /// Counts the alive neighbors in the given neighborhood instead of the
+    /// current one.
+    ///
+    /// Fails if a count is larger than the number of neighbors or the rule
+    /// is non-totalistic and the neighborhood isn't the Moore neighborhood.
+    pub fn with_neighborhood(self, neighborhood: Neighborhood) -> Result<Self, RuleError> {
+        if !self.is_totalistic() && neighborhood != Neighborhood::Moore {
+            return Err(RuleError::Unsupported(
+                "Hensel notation needs the Moore neighborhood".to_string(),
+            ));
+        }
+        let max = neighborhood.size();
+        let too_large = (max + 1..64).find(|&n| self.is_birth(n) || self.is_survival(n));
+        if let Some(count) = too_large {
+            return Err(RuleError::CountOutOfRange(count, max));
+        }
+        Ok(Self {
+            neighborhood,
+            ..self
+        })
+    }
+
+    /// Turns this into a Generations rule with the given number of states
+    /// (including alive and dead).
+    pub fn with_states(self, states: u8) -> Result<Self, RuleError> {
+        if states < 2 {
+            return Err(RuleError::StatesOutOfRange(states.into()));
+        }
+        Ok(Self { states, ..self })
+    }
+
+    /// Gives alive cells the given number of colors.
+    pub fn with_colors(self, colors: u8) -> Result<Self, RuleError> {
+        if colors == 0 || colors > MAX_COLORS {
+            return Err(RuleError::ColorsOutOfRange(colors.into()));
+        }
+        Ok(Self { colors, ..self })
+    }
+
+    /// Number of states including alive and dead, `2` unless this is a
+    /// Generations rule.
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    /// Number of colors of alive cells, `1` unless this is a multi-color
+    /// rule.
+    pub fn colors(&self) -> u8 {
+        self.colors
+    }
+
+    /// Color of a cell born from alive neighbors of the given colors.
+    ///
+    /// That is the most common color among them. On a tie it is the lowest
+    /// color none of them has if there is one, so a QuadLife cell born from
+    /// three different colors takes the fourth, and otherwise the lowest of
+    /// the most common colors.
+    pub fn birth_color(&self, neighbor_colors: impl IntoIterator<Item = u8>) -> u8 {
+        let mut counts = [0usize; MAX_COLORS as usize];
+        for color in neighbor_colors {
+            counts[(color as usize).min(self.colors as usize - 1)] += 1;
+        }
+        let counts = &counts[..self.colors as usize];
+        let most = counts.iter().copied().max().unwrap_or(0);
+        let mut most_common = (0..self.colors).filter(|&c| counts[c as usize] == most);
+        let first = most_common.next().unwrap_or(0);
+        if most_common.next().is_none() {
+            return first;
+        }
+        (0..self.colors)
+            .find(|&c| counts[c as usize] == 0)
+            .unwrap_or(first)
+    }
+
+    pub fn neighborhood(&self) -> Neighborhood {
+        self.neighborhood
+    }
+
+    /// Whether the next state only depends on the number of alive neighbors,
+    /// which is the case unless the rule uses Hensel notation.
+    pub fn is_totalistic(&self) -> bool {
+        self.hensel.is_none()
+    }
+
+    /// Conway's Game of Life (`B3/S23`).
+    pub fn conway() -> Self {
+        Self::new(&[3], &[2, 3]).unwrap()
+    }
+
+    pub fn is_birth(&self, alive_neighbors: usize) -> bool {
+        alive_neighbors < 64 && self.birth & (1 << alive_neighbors) != 0
+    }
+
+    pub fn is_survival(&self, alive_neighbors: usize) -> bool {
+        alive_neighbors < 64 && self.survival & (1 << alive_neighbors) != 0
+    }
+
+    /// State of a cell in the next generation.
+    ///
+    /// Cells are born with color `0`, universes supporting multi-color rules
+    /// color them with [`Rule::birth_color`].
+    pub fn next_state(&self, state: CellState, alive_neighbors: usize) -> CellState {
+        match state {
+            CellState::Dead if self.is_birth(alive_neighbors) => CellState::Alive(0),
+            CellState::Dead => CellState::Dead,
+            CellState::Alive(_) if self.is_survival(alive_neighbors) => state,
+            CellState::Alive(_) => decay(self.states, 0),
+            CellState::Dying(age) => decay(self.states, age),
+            // states of other families are left alone
+            CellState::Conductor | CellState::ElectronHead | CellState::ElectronTail => state,
+        }
+    }
+
+    /// State of a cell in the next generation of a Moore rule, where bit `i`
+    /// of `neighbors` is set if the neighbor at the `i`-th offset of
+    /// [`Neighborhood::offsets`] is alive.
+    ///
+    /// Works for totalistic and non-totalistic rules.
+    pub fn next_state_moore(&self, state: CellState, neighbors: u8) -> CellState {
+        let hensel = match self.hensel {
+            Some(hensel) => hensel,
+            None => return self.next_state(state, neighbors.count_ones() as usize),
+        };
+        match state {
+            CellState::Dead if hensel.birth.contains(neighbors) => CellState::Alive(0),
+            CellState::Dead => CellState::Dead,
+            CellState::Alive(_) if hensel.survival.contains(neighbors) => state,
+            CellState::Alive(_) => decay(self.states, 0),
+            CellState::Dying(age) => decay(self.states, age),
+            CellState::Conductor | CellState::ElectronHead | CellState::ElectronTail => state,
+        }
+    }
+}
+
+/// State after a cell of a rule with `states` states was dying for `age`
+/// generations (0 if it was alive).
+pub(crate) fn decay(states: u8, age: u8) -> CellState {
+    if (age as u16 + 2) < states as u16 {
+        CellState::Dying(age + 1)
+    } else {
+        CellState::Dead
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Self::conway()
+    }
+}
+
+fn counts_to_mask(counts: &[u8], max: usize) -> Result<u64, RuleError> {
+    counts.iter().try_fold(0, |mask, &count| {
+        if count as usize > max {
+            Err(RuleError::CountOutOfRange(count.into(), max))
+        } else {
+            Ok(mask | 1 << count)
+        }
+    })
+}
+
+fn parse_counts(digits: &str) -> Result<Vec<u8>, RuleError> {
+    if digits.contains(',') {
+        // a single count ends with a comma
+        let list = digits.strip_suffix(',').unwrap_or(digits);
+        return list
+            .split(',')
+            .map(|count| {
+                if let Some(c) = count.chars().find(|c| !c.is_ascii_digit()) {
+                    return Err(RuleError::UnexpectedChar(c));
+                }
+                count
+                    .parse()
+                    .map_err(|_| RuleError::Malformed(digits.to_string()))
+            })
+            .collect();
+    }
+    digits
+        .chars()
+        .map(|c| {
+            c.to_digit(MAX_DIGIT as u32 + 1)
+                .map(|d| d as u8)
+                .ok_or(RuleError::UnexpectedChar(c))
+        })
+        .collect()
+}
+
+/// Parses the counts of a Moore rule, where every count may be followed by
+/// Hensel letters or `-` and the letters it excludes.
+///
+/// Also returns the allowed configurations if any count has letters.
+fn parse_hensel(digits: &str) -> Result<(Vec<u8>, Option<Configurations>), RuleError> {
+    let mut counts = Vec::new();
+    let mut configurations = Configurations::default();
+    let mut restricted = false;
+    let mut chars = digits.chars().peekable();
+    while let Some(c) = chars.next() {
+        let count = c.to_digit(10).ok_or(RuleError::UnexpectedChar(c))? as usize;
+        if count > MAX_MOORE_DIGIT.into() {
+            return Err(RuleError::CountOutOfRange(count, MAX_MOORE_DIGIT.into()));
+        }
+        let negated = chars.next_if_eq(&'-').is_some();
+        let mut letters = String::new();
+        while let Some(letter) = chars.next_if(char::is_ascii_alphabetic) {
+            let letter = letter.to_ascii_lowercase();
+            if !hensel::letters(count).contains(letter) {
+                return Err(RuleError::UnexpectedChar(letter));
+            }
+            letters.push(letter);
+        }
+        if negated && letters.is_empty() {
+            return Err(RuleError::UnexpectedChar('-'));
+        }
+
+        if letters.is_empty() {
+            counts.push(count as u8);
+            hensel::all(count).for_each(|c| configurations.insert(c));
+            continue;
+        }
+        restricted = true;
+        let mut included = hensel::letters(count)
+            .chars()
+            .filter(|&letter| letters.contains(letter) != negated)
+            .peekable();
+        if included.peek().is_some() {
+            counts.push(count as u8);
+        }
+        for letter in included {
+            for c in hensel::with_letter(count, letter) {
+                configurations.insert(c);
+            }
+        }
+    }
+    Ok((counts, Some(configurations).filter(|_| restricted)))
+}
+
+/// Writes the counts of a non-totalistic rule in Hensel notation, preferring
+/// the excluded letters if there are fewer of them.
+fn hensel_counts(configurations: &Configurations) -> String {
+    let mut s = String::new();
+    for count in 0..=8 {
+        let letters = hensel::letters(count);
+        if letters.is_empty() {
+            if hensel::all(count).any(|c| configurations.contains(c)) {
+                s.push_str(&count.to_string());
+            }
+            continue;
+        }
+        let (included, excluded): (String, String) = letters
+            .chars()
+            .partition(|&letter| configurations.contains(hensel::with_letter(count, letter)[0]));
+        if included.is_empty() {
+            continue;
+        }
+        s.push_str(&count.to_string());
+        if excluded.is_empty() {
+            continue;
+        }
+        if included.len() <= excluded.len() {
+            s.push_str(&included);
+        } else {
+            s.push('-');
+            s.push_str(&excluded);
+        }
+    }
+    s
+}
+
+/// Splits the letter of the neighborhood off the end of a rule.
+fn split_neighborhood(s: &str) -> (&str, Neighborhood) {
+    let upper = s.to_ascii_uppercase();
+    let suffixes = [
+        Neighborhood::VonNeumann,
+        Neighborhood::Hexagonal,
+        Neighborhood::TriangularEdges,
+        Neighborhood::Triangular,
+    ];
+    for &neighborhood in &suffixes {
+        let suffix = neighborhood_suffix(neighborhood);
+        if upper.ends_with(suffix) {
+            return (&s[..s.len() - suffix.len()], neighborhood);
+        }
+    }
+    (s, Neighborhood::Moore)
+}
+
+fn neighborhood_suffix(neighborhood: Neighborhood) -> &'static str {
+    match neighborhood {
+        Neighborhood::Moore | Neighborhood::Custom(_) => "",
+        Neighborhood::VonNeumann => "V",
+        Neighborhood::Hexagonal => "H",
+        Neighborhood::TriangularEdges => "LE",
+        Neighborhood::Triangular => "L",
+    }
+}
+
+impl FromStr for Rule {
+    type Err = RuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut birth = None;
+        let mut survival = None;
+        let mut states = None;
+        let mut colors = None;
+
+        let (rule, neighborhood) = split_neighborhood(s.trim());
+        for part in rule.split('/') {
+            let mut chars = part.chars();
+            let (target, letter) = match chars.next() {
+                Some(c @ 'B') | Some(c @ 'b') => (&mut birth, c),
+                Some(c @ 'S') | Some(c @ 's') => (&mut survival, c),
+                Some('C') | Some('c') => {
+                    if states.is_some() {
+                        return Err(RuleError::Duplicate('C'));
+                    }
+                    let n = parse_number(chars.as_str(), s)?;
+                    states.replace(u8::try_from(n).map_err(|_| RuleError::StatesOutOfRange(n))?);
+                    continue;
+                }
+                Some('K') | Some('k') => {
+                    if colors.is_some() {
+                        return Err(RuleError::Duplicate('K'));
+                    }
+                    let n = parse_number(chars.as_str(), s)?;
+                    colors.replace(u8::try_from(n).map_err(|_| RuleError::ColorsOutOfRange(n))?);
+                    continue;
+                }
+                Some(c) => return Err(RuleError::UnexpectedChar(c)),
+                None => return Err(RuleError::Malformed(s.to_string())),
+            };
+            if target.is_some() {
+                return Err(RuleError::Duplicate(letter.to_ascii_uppercase()));
+            }
+            let digits = chars.as_str();
+            if neighborhood == Neighborhood::Moore && !digits.contains(',') {
+                target.replace(parse_hensel(digits)?);
+            } else {
+                target.replace((parse_counts(digits)?, None));
+            }
+        }
+
+        match (birth, survival) {
+            (Some((birth, birth_hensel)), Some((survival, survival_hensel))) => {
+                // a part without letters allows all configurations of its
+                // counts
+                let hensel = if birth_hensel.is_some() || survival_hensel.is_some() {
+                    Some(Hensel {
+                        birth: birth_hensel.unwrap_or_else(|| Configurations::with_counts(&birth)),
+                        survival: survival_hensel
+                            .unwrap_or_else(|| Configurations::with_counts(&survival)),
+                    })
+                } else {
+                    None
+                };
+                let max = match neighborhood {
+                    Neighborhood::Moore => MAX_NEIGHBORS.into(),
+                    _ => neighborhood.size(),
+                };
+                let rule = Rule {
+                    birth: counts_to_mask(&birth, max)?,
+                    survival: counts_to_mask(&survival, max)?,
+                    states: 2,
+                    colors: 1,
+                    neighborhood,
+                    hensel,
+                };
+                rule.with_states(states.unwrap_or(2))?
+                    .with_colors(colors.unwrap_or(1))
+            }
+            _ => Err(RuleError::Malformed(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let max_digit = match self.neighborhood {
+            Neighborhood::Moore | Neighborhood::Custom(_) => MAX_MOORE_DIGIT,
+            _ => MAX_DIGIT,
+        };
+        let counts = |mask: u64| -> String {
+            let counts = (0..=MAX_NEIGHBORS).filter(|n| mask & (1 << n) != 0);
+            if mask >> (max_digit + 1) != 0 {
+                let counts: Vec<_> = counts.map(|n| n.to_string()).collect();
+                match counts.as_slice() {
+                    [count] => format!("{},", count),
+                    _ => counts.join(","),
+                }
+            } else {
+                counts
+                    .filter_map(|n| std::char::from_digit(n.into(), MAX_DIGIT as u32 + 1))
+                    .collect()
+            }
+        };
+        match self.hensel {
+            Some(hensel) => write!(
+                f,
+                "B{}/S{}",
+                hensel_counts(&hensel.birth),
+                hensel_counts(&hensel.survival)
+            )?,
+            None => write!(f, "B{}/S{}", counts(self.birth), counts(self.survival))?,
+        }
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        if self.colors > 1 {
+            write!(f, "/K{}", self.colors)?;
+        }
+        write!(f, "{}", neighborhood_suffix(self.neighborhood))
+    }
+}
+
+impl TryFrom<String> for Rule {
+    type Error = RuleError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Rule> for String {
+    fn from(rule: Rule) -> Self {
+        rule.to_string()
+    }
+}
+
+/// Error when parsing a [`Rule`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleError {
+    /// The rule is not of the form `B<counts>/S<counts>[/C<states>]` or in
+    /// the notation of another family of rules.
+    Malformed(String),
+    /// `B`, `S`, `C` or `K` appears more than once.
+    Duplicate(char),
+    UnexpectedChar(char),
+    /// A neighbor count is larger than the number of neighbors.
+    CountOutOfRange(usize, usize),
+    /// Generations rules need at least 2 states.
+    StatesOutOfRange(u32),
+    /// Multi-color rules have between 1 and [`MAX_COLORS`] colors.
+    ColorsOutOfRange(u32),
+    /// The rule is valid but can't be used with the chosen universe.
+    Unsupported(String),
+    /// A custom neighborhood has a neighbor at this offset, which is more than
+    /// [`MAX_RANGE`] cells away along an axis.
+    NeighborOutOfRange(i32, i32),
+    /// The range of a Larger than Life rule is 0 or larger than
+    /// [`MAX_LTL_RANGE`].
+    RangeOutOfRange(u32),
+    /// No rule with this name is registered in the
+    /// [`crate::RuleRegistry`].
+    UnknownRule(String),
+    /// A rule with this name is already registered in the
+    /// [`crate::RuleRegistry`].
+    DuplicateRule(String),
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleError::Malformed(rule) => write!(
+                f,
+                "invalid rule {:?}, expected B/S notation like \"B3/S23\" or Larger than \
+                 Life notation like \"R5,C0,M1,S34..58,B34..45,NM\"",
+                rule
+            ),
+            RuleError::Duplicate(letter) => write!(f, "'{}' is given more than once", letter),
+            RuleError::UnexpectedChar(c) => write!(f, "unexpected character {:?} in rule", c),
+            RuleError::CountOutOfRange(count, max) => write!(
+                f,
+                "neighbor count {} is out of range, must be at most {}",
+                count, max
+            ),
+            RuleError::StatesOutOfRange(states) => write!(
+                f,
+                "number of states {} is out of range, must be between 2 and {}",
+                states, MAX_STATES
+            ),
+            RuleError::ColorsOutOfRange(colors) => write!(
+                f,
+                "number of colors {} is out of range, must be between 1 and {}",
+                colors, MAX_COLORS
+            ),
+            RuleError::Unsupported(reason) => write!(f, "unsupported rule: {}", reason),
+            RuleError::NeighborOutOfRange(dx, dy) => write!(
+                f,
+                "neighbor at offset ({}, {}) is out of range, must be at most {} cells away",
+                dx, dy, MAX_RANGE
+            ),
+            RuleError::RangeOutOfRange(range) => write!(
+                f,
+                "range {} is out of range, must be between 1 and {}",
+                range, MAX_LTL_RANGE
+            ),
+            RuleError::UnknownRule(name) => write!(f, "no rule named {:?} is registered", name),
+            RuleError::DuplicateRule(name) => {
+                write!(f, "a rule named {:?} is already registered", name)
+            }
+        }
+    }
+}
+
+impl Error for RuleError {}
@@ -0,0 +1,114 @@
+use std::mem;
+
+use crate::{Board, Bounds, CellState, Rule, RuleError, Topology, Universe};
+
+/// Dense finite board running a Life-like rule as a second-order rule.
+///
+/// A cell is alive in the next generation if the rule would make it alive
+/// in the current one, unless it was alive in the previous generation (and
+/// the other way round). The previous generation can be computed the same way
+/// from the current and the next one, so [`Universe::reverse`] runs the
+/// simulation exactly backwards without storing more than two generations.
+///
+/// Cells written with [`Universe::set`] only change the current generation,
+/// all cells of the previous generation are dead at the start.
+#[derive(Debug, Clone)]
+pub struct SecondOrderBoard {
+    bounds: Bounds,
+    board: Board,
+    previous: Vec<bool>,
+    // becomes the previous generation in every step
+    current: Vec<bool>,
+}
+
+impl SecondOrderBoard {
+    /// Fails for rules with more than two states or more than one color.
+    pub fn new(bounds: Bounds, rule: Rule) -> Result<Self, RuleError> {
+        if rule.states() > 2 || rule.colors() > 1 {
+            return Err(RuleError::Unsupported(
+                "second-order rules need a rule with two states and one color".to_string(),
+            ));
+        }
+        let len = bounds.width() * bounds.height();
+        Ok(Self {
+            bounds,
+            board: Board::new(bounds, rule),
+            previous: vec![false; len],
+            current: Vec::with_capacity(len),
+        })
+    }
+
+    pub fn with_topology(self, topology: Topology) -> Self {
+        Self {
+            board: self.board.with_topology(topology),
+            ..self
+        }
+    }
+
+    pub fn rule(&self) -> Rule {
+        self.board.rule()
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.board.topology()
+    }
+}
+
+fn cells(bounds: Bounds) -> impl Iterator<Item = (i32, i32)> {
+    (bounds.min_y..=bounds.max_y)
+        .flat_map(move |y| (bounds.min_x..=bounds.max_x).map(move |x| (x, y)))
+}
+
+/// Flips the cells of `board` where `flipped` is set.
+fn flip(bounds: Bounds, board: &mut Board, flipped: &[bool]) {
+    for ((x, y), _) in cells(bounds).zip(flipped).filter(|(_, &flipped)| flipped) {
+        let state = if board.get(x, y).is_alive() {
+            CellState::Dead
+        } else {
+            CellState::Alive(0)
+        };
+        board.set(x, y, state);
+    }
+}
+
+impl Universe for SecondOrderBoard {
+    fn step(&mut self) {
+        let mut current = mem::take(&mut self.current);
+        current.clear();
+        current.extend(cells(self.bounds).map(|(x, y)| self.board.get(x, y).is_alive()));
+        self.board.step();
+        flip(self.bounds, &mut self.board, &self.previous);
+        self.current = mem::replace(&mut self.previous, current);
+    }
+
+    fn reverse(&mut self) -> bool {
+        // the generation after the current one becomes the previous one, so
+        // the next step computes the one before
+        let mut board = self.board.clone();
+        board.step();
+        for ((x, y), previous) in cells(self.bounds).zip(&mut self.previous) {
+            *previous = board.get(x, y).is_alive() != *previous;
+        }
+        true
+    }
+
+    fn get(&self, x: i32, y: i32) -> CellState {
+        self.board.get(x, y)
+    }
+
+    fn set(&mut self, x: i32, y: i32, state: CellState) {
+        self.board.set(x, y, state);
+    }
+
+    fn population(&self) -> usize {
+        self.board.population()
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Some(self.bounds)
+    }
+
+    fn generation(&self) -> u64 {
+        self.board.generation()
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use crate::{Bounds, CellState, Rule, RuleError, Universe};
+
+/// Unbounded universe that only stores cells which are not dead.
+///
+/// Each step only looks at those cells and their neighbors, so patterns can
+/// grow in any direction without being clipped.
+#[derive(Debug, Clone)]
+pub struct SparseUniverse {
+    rule: Rule,
+    cells: HashMap<(i32, i32), CellState>,
+    generation: u64,
+}
+
+impl SparseUniverse {
+    /// Fails for rules with `B0` because they would fill the infinite plane
+    /// in a single step.
+    pub fn new(rule: Rule) -> Result<Self, RuleError> {
+        if rule.is_birth(0) {
+            return Err(RuleError::Unsupported(
+                "B0 rules need a bounded board".to_string(),
+            ));
+        }
+        Ok(Self {
+            rule,
+            cells: HashMap::new(),
+            generation: 0,
+        })
+    }
+
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Color of a cell born at `(x, y)`, for multi-color rules.
+    fn birth_color(&self, x: i32, y: i32) -> u8 {
+        let colors = self
+            .rule
+            .neighborhood()
+            .offsets(x, y)
+            .filter_map(|(dx, dy)| match self.get(x + dx, y + dy) {
+                CellState::Alive(color) => Some(color),
+                _ => None,
+            });
+        self.rule.birth_color(colors)
+    }
+}
+
+impl Universe for SparseUniverse {
+    fn step(&mut self) {
+        // non-totalistic rules need to know which neighbors are alive, the
+        // cell at offset i sees this cell at the opposite offset 7 - i
+        let totalistic = self.rule.is_totalistic();
+        let mut alive_neighbors: HashMap<(i32, i32), usize> = HashMap::new();
+        for (&(x, y), state) in &self.cells {
+            if !state.is_alive() {
+                continue;
+            }
+            // the cells this cell is a neighbor of
+            let offsets = self.rule.neighborhood().mirrored().offsets(x, y);
+            for (i, (dx, dy)) in offsets.enumerate() {
+                let neighbors = alive_neighbors.entry((x + dx, y + dy)).or_insert(0);
+                if totalistic {
+                    *neighbors += 1;
+                } else {
+                    *neighbors |= 1 << (7 - i);
+                }
+            }
+        }
+
+        let mut next = HashMap::with_capacity(self.cells.len());
+        // cells that aren't dead and the dead cells next to alive ones
+        let candidates = self.cells.keys().chain(
+            alive_neighbors
+                .keys()
+                .filter(|c| !self.cells.contains_key(c)),
+        );
+        for &pos in candidates {
+            let state = self.get(pos.0, pos.1);
+            let neighbors = alive_neighbors.get(&pos).copied().unwrap_or(0);
+            let next_state = if totalistic {
+                self.rule.next_state(state, neighbors)
+            } else {
+                self.rule.next_state_moore(state, neighbors as u8)
+            };
+            let state = match next_state {
+                CellState::Alive(_) if self.rule.colors() > 1 && !state.is_alive() => {
+                    CellState::Alive(self.birth_color(pos.0, pos.1))
+                }
+                next_state => next_state,
+            };
+            if state != CellState::Dead {
+                next.insert(pos, state);
+            }
+        }
+
+        self.cells = next;
+        self.generation += 1;
+    }
+
+    fn get(&self, x: i32, y: i32) -> CellState {
+        self.cells.get(&(x, y)).copied().unwrap_or(CellState::Dead)
+    }
+
+    fn set(&mut self, x: i32, y: i32, state: CellState) {
+        if state == CellState::Dead {
+            self.cells.remove(&(x, y));
+        } else {
+            self.cells.insert((x, y), state);
+        }
+    }
+
+    fn population(&self) -> usize {
+        self.cells.len()
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        None
+    }
+
+    fn live_cells(&self, area: &Bounds) -> Vec<(i32, i32, CellState)> {
+        self.cells
+            .iter()
+            .filter(|(&(x, y), _)| area.contains(x, y))
+            .map(|(&(x, y), &state)| (x, y, state))
+            .collect()
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+}
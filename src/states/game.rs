@@ -1,6 +1,3 @@
-use std::collections::HashMap;
-use std::collections::HashSet;
-
 use amethyst::assets::Handle;
 use amethyst::core::transform::Parent;
 use amethyst::core::transform::Transform;
@@ -10,11 +7,21 @@ use amethyst::renderer::palette::Srgba;
 use amethyst::renderer::resources::Tint;
 use amethyst::renderer::SpriteRender;
 use amethyst::renderer::SpriteSheet;
-use nalgebra::base::Vector3;
 
 use serde::{Deserialize, Serialize};
 
-use crate::systems::{Cell, CellState, Neighbors, ScreenParent};
+use game_of_live::{
+    Automaton, Board, Bounds, CellState, ChunkedUniverse, CustomBoard, CustomRule, Cyclic,
+    CyclicBoard, Direction, Grid, HashLife, LargerThanLife, Lenia, LeniaBoard, LtlBoard, Margolus,
+    MargolusBoard, Neighborhood, PackedBoard, Rule, RuleError, RuleRegistry, SecondOrderBoard,
+    SparseUniverse, StaticBoard, Topology, Turmite, TurmiteRule, Universe, WireworldBoard, Wolfram,
+    WolframBoard, MAX_JUMP_EXPONENT,
+};
+
+use crate::systems::{
+    cell_tint, cell_transform, level_tint, sprite_number, turmite_transform, Cell, RunConfig,
+    ScreenParent, TurmiteAgent, VisibleCells, TURMITE_SPRITE,
+};
 
 #[derive(Debug)]
 pub struct GameState {
@@ -32,109 +39,72 @@ impl SimpleState for GameState {
             .build();
 
         let board: BoardConfig = (*world.read_resource::<BoardConfig>()).clone();
+        let (rule, universe) = {
+            let mut run_config = world.write_resource::<RunConfig>();
+            run_config
+                .select_rule(&world.read_resource::<RuleRegistry>())
+                .unwrap_or_else(|e| panic!("Invalid run config: {}", e));
+            let universe = board
+                .build_universe(&run_config)
+                .unwrap_or_else(|e| panic!("Invalid board config: {}", e));
+            (run_config.rule.clone(), universe)
+        };
 
-        let mut alives = HashSet::new();
-
-        for y in 0..board.board.len() {
-            let row = &board.board[y];
-            for x in 0..row.len() {
-                if row[x] == 1 {
-                    alives.insert((x as i32, y as i32));
+        if board.engine == Engine::Dense {
+            let grid = rule.grid();
+            for y in board.min_y..=board.max_y {
+                for x in board.min_x..=board.max_x {
+                    let state = universe.get(x, y);
+                    let (sprite_number, tint) = if rule.is_continuous() {
+                        (
+                            sprite_number(CellState::Alive(0), grid),
+                            level_tint(universe.level(x, y)),
+                        )
+                    } else {
+                        (sprite_number(state, grid), cell_tint(state, &rule))
+                    };
+                    world
+                        .create_entity()
+                        .with(Cell { x, y, state })
+                        .with(Parent::new(parent_entity))
+                        .with(cell_transform(x, y, board.tile_size, grid))
+                        .with(SpriteRender {
+                            sprite_sheet: self.sprite_sheet_handle.clone(),
+                            sprite_number,
+                        })
+                        .with(tint)
+                        .build();
                 }
             }
+        } else {
+            // too many cells to spawn all of them, only the visible ones are
+            // spawned while the game is running
+            world.insert(VisibleCells::new(
+                self.sprite_sheet_handle.clone(),
+                parent_entity,
+                board.tile_size,
+            ));
         }
 
-        let mut entities = HashMap::new();
-
-        for y in board.min_y..=board.max_y {
-            for x in board.min_x..=board.max_x {
-                let mut text_transform = Transform::default();
-                text_transform.set_translation_xyz(
-                    x as f32 * board.tile_size,
-                    y as f32 * board.tile_size,
-                    0.0,
-                );
-                text_transform.set_scale(Vector3::new(
-                    board.tile_size / 8.0,
-                    board.tile_size / 8.0,
-                    1.0,
-                ));
-
-                let alive = alives.contains(&(x, y));
-                let entity = world
-                    .create_entity()
-                    .with(Cell {
-                        x,
-                        y,
-                        state: if alive {
-                            CellState::Alive
-                        } else {
-                            CellState::Dead
-                        },
-                    })
-                    .with(Parent::new(parent_entity))
-                    .with(text_transform)
-                    .with(SpriteRender {
-                        sprite_sheet: self.sprite_sheet_handle.clone(),
-                        sprite_number: if alive { 0 } else { 1 },
-                    })
-                    .with(Tint(Srgba::new(0.5, 0.5, 0.5, 1.0)))
-                    .build();
-
-                entities.insert((x, y), entity);
-            }
+        for config in &board.turmites {
+            let turmite = Turmite::new(config.x, config.y, config.direction);
+            world
+                .create_entity()
+                .with(Parent::new(parent_entity))
+                .with(turmite_transform(&turmite, board.tile_size))
+                .with(SpriteRender {
+                    sprite_sheet: self.sprite_sheet_handle.clone(),
+                    sprite_number: TURMITE_SPRITE,
+                })
+                .with(Tint(Srgba::new(1.0, 0.2, 0.2, 1.0)))
+                .with(TurmiteAgent {
+                    turmite,
+                    rule: config.rule.clone(),
+                })
+                .build();
         }
 
-        world.exec(|mut neighbors_store: WriteStorage<Neighbors>| {
-            for y in board.min_y..=board.max_y {
-                for x in board.min_x..=board.max_x {
-                    let entity = entities.get(&(x, y)).unwrap();
-                    let neighbors = Neighbors {
-                        n: if y == board.min_y {
-                            None
-                        } else {
-                            Some(entities.get(&(x, y - 1)).unwrap().clone())
-                        },
-                        ne: if y == board.min_y || x == board.max_x {
-                            None
-                        } else {
-                            Some(entities.get(&(x + 1, y - 1)).unwrap().clone())
-                        },
-                        e: if x == board.max_x {
-                            None
-                        } else {
-                            Some(entities.get(&(x + 1, y)).unwrap().clone())
-                        },
-                        se: if x == board.max_x || y == board.max_y {
-                            None
-                        } else {
-                            Some(entities.get(&(x + 1, y + 1)).unwrap().clone())
-                        },
-                        s: if y == board.max_y {
-                            None
-                        } else {
-                            Some(entities.get(&(x, y + 1)).unwrap().clone())
-                        },
-                        sw: if y == board.max_x || x == board.min_x {
-                            None
-                        } else {
-                            Some(entities.get(&(x - 1, y + 1)).unwrap().clone())
-                        },
-                        w: if x == board.min_x {
-                            None
-                        } else {
-                            Some(entities.get(&(x - 1, y)).unwrap().clone())
-                        },
-                        nw: if x == board.min_x || y == board.min_y {
-                            None
-                        } else {
-                            Some(entities.get(&(x - 1, y - 1)).unwrap().clone())
-                        },
-                    };
-                    neighbors_store.insert(*entity, neighbors).unwrap();
-                }
-            }
-        });
+        world.insert(universe);
     }
 
     fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
@@ -142,25 +112,366 @@ impl SimpleState for GameState {
     }
 }
 
+/// Largest jump of engines that step through every generation, so a jump
+/// doesn't freeze the game for long.
+const MAX_STEPPED_JUMP_EXPONENT: u32 = 16;
+
+/// Which [`Universe`] implementation runs the simulation.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum Engine {
+    /// Finite board between `min_x..=max_x` and `min_y..=max_y`.
+    #[default]
+    Dense,
+    /// Unbounded board that only stores alive cells. Ignores the bounds and
+    /// the topology.
+    Sparse,
+    /// Unbounded HashLife quadtree, best for jumping far ahead. Ignores the
+    /// bounds and the topology.
+    HashLife,
+    /// Finite board like `Dense` but stores 64 cells per word. Use this for
+    /// boards with millions of cells.
+    Packed,
+    /// Unbounded board of 64x64 chunks that are stepped in parallel, best
+    /// for large, busy patterns. Ignores the bounds and the topology.
+    Chunked,
+}
+
+/// Neighborhood used instead of the one of the rule.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub enum NeighborhoodConfig {
+    Moore,
+    VonNeumann,
+    /// Rows of the same odd length where `1` marks a neighbor of the cell in
+    /// the middle, e.g. `[[0, 1, 0], [1, 0, 1], [0, 1, 0]]` for Von Neumann.
+    Custom(Vec<Vec<u8>>),
+}
+
+impl NeighborhoodConfig {
+    fn neighborhood(&self) -> Result<Neighborhood, RuleError> {
+        let mask = match self {
+            NeighborhoodConfig::Moore => return Ok(Neighborhood::Moore),
+            NeighborhoodConfig::VonNeumann => return Ok(Neighborhood::VonNeumann),
+            NeighborhoodConfig::Custom(mask) => mask,
+        };
+        let height = mask.len();
+        let width = mask.first().map(Vec::len).unwrap_or(0);
+        if height % 2 == 0 || width % 2 == 0 || mask.iter().any(|row| row.len() != width) {
+            return Err(RuleError::Unsupported(
+                "custom neighborhoods need rows of the same odd length".to_string(),
+            ));
+        }
+        let mut offsets = Vec::new();
+        for (y, row) in mask.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                if cell != 0 {
+                    offsets.push((x as i32 - width as i32 / 2, y as i32 - height as i32 / 2));
+                }
+            }
+        }
+        Neighborhood::custom(&offsets)
+    }
+}
+
+/// Turmite placed on the board at the start.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TurmiteConfig {
+    x: i32,
+    y: i32,
+    #[serde(default)]
+    direction: Direction,
+    /// Rule table like `"RL"`, Langton's ant if left out.
+    #[serde(default)]
+    rule: TurmiteRule,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct BoardConfig {
     tile_size: f32,
+    #[serde(default)]
+    engine: Engine,
     min_x: i32,
     max_x: i32,
     min_y: i32,
     max_y: i32,
+    /// How the edges of the board are connected.
+    #[serde(default)]
+    topology: Topology,
+    /// Replaces the neighborhood of the rule if set, only for rules on a
+    /// square grid.
+    #[serde(default)]
+    neighborhood: Option<NeighborhoodConfig>,
+    /// Parameters of the `Lenia` rule, Orbium if left out.
+    #[serde(default)]
+    lenia: Lenia,
     board: Vec<Vec<u8>>,
+    /// Turmites walking over the board, only on a square grid.
+    #[serde(default)]
+    turmites: Vec<TurmiteConfig>,
+}
+
+impl BoardConfig {
+    fn bounds(&self) -> Bounds {
+        Bounds::new(self.min_x, self.max_x, self.min_y, self.max_y)
+    }
+
+    /// Largest `jump_exponent` of the engine, engines other than HashLife
+    /// step through every generation of a jump.
+    pub fn max_jump_exponent(&self) -> u32 {
+        match self.engine {
+            Engine::HashLife => MAX_JUMP_EXPONENT,
+            _ => MAX_STEPPED_JUMP_EXPONENT,
+        }
+    }
+
+    pub fn tile_size(&self) -> f32 {
+        self.tile_size
+    }
+
+    /// Creates the simulation described by this config running the rule of
+    /// `run`, made random by its stochastic probabilities if it has any.
+    ///
+    /// `board` is the initial pattern with its first row and column at
+    /// `(0, 0)`, where `0` is a dead and `1` an alive cell. Higher numbers
+    /// are dying cells of Generations rules or the colors of multi-color
+    /// rules, starting at `1` for the first one. Wireworld boards use `1` for
+    /// electron heads, `2` for tails and `3` for conductors. One-dimensional
+    /// rules only use the first row as the first generation at the top of
+    /// the board. Lenia boards give the level of a cell in tenths, from `0`
+    /// to `10`. Cyclic rules start with random states drawn from the seed of
+    /// `run`, which the board replaces with the states numbered from `1`
+    /// where it isn't `0`.
+    pub fn build_universe(&self, run: &RunConfig) -> Result<Box<dyn Universe>, RuleError> {
+        let rule = &run.rule;
+        if !self.turmites.is_empty() && rule.grid() != Grid::Square {
+            return Err(RuleError::Unsupported(
+                "turmites only walk on a square grid".to_string(),
+            ));
+        }
+        if run.stochastic.is_some() && !matches!(rule, Automaton::Life(_)) {
+            return Err(RuleError::Unsupported(
+                "only Life-like rules can be stochastic".to_string(),
+            ));
+        }
+        let mut universe = match rule {
+            Automaton::Life(rule) => self.build_life(*rule, run)?,
+            Automaton::SecondOrder(rule) => self.build_second_order(*rule)?,
+            Automaton::LargerThanLife(rule) => self.build_larger_than_life(*rule)?,
+            Automaton::Cyclic(rule) => return self.build_cyclic(*rule, run.seed),
+            Automaton::Wolfram(rule) => return self.build_wolfram(*rule),
+            Automaton::Margolus(rule) => self.build_margolus(*rule)?,
+            Automaton::Wireworld => self.build_wireworld()?,
+            Automaton::Static(_) => self.build_static()?,
+            Automaton::Lenia => return self.build_lenia(),
+            Automaton::Custom(rule) => self.build_custom(rule.clone())?,
+        };
+        for (y, row) in self.board.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                universe.set(x as i32, y as i32, rule.cell_state(*cell));
+            }
+        }
+        Ok(universe)
+    }
+
+    /// Gives a Life-like rule the board's neighborhood, if it has one.
+    fn life_rule(&self, rule: Rule) -> Result<Rule, RuleError> {
+        let neighborhood = match &self.neighborhood {
+            Some(_) if rule.neighborhood().grid() != Grid::Square => {
+                return Err(RuleError::Unsupported(
+                    "the board's neighborhood only works with rules on a square grid".to_string(),
+                ))
+            }
+            Some(neighborhood) => neighborhood.neighborhood()?,
+            None => rule.neighborhood(),
+        };
+        // also checks the counts of rules without a neighborhood letter
+        rule.with_neighborhood(neighborhood)
+    }
+
+    fn build_life(&self, rule: Rule, run: &RunConfig) -> Result<Box<dyn Universe>, RuleError> {
+        let rule = self.life_rule(rule)?;
+        if let Some(stochastic) = &run.stochastic {
+            if self.engine != Engine::Dense {
+                return Err(RuleError::Unsupported(
+                    "stochastic rules only run on the Dense engine".to_string(),
+                ));
+            }
+            let board = Board::new(self.bounds(), rule)
+                .with_topology(self.topology)
+                .with_stochastic(stochastic.clone(), run.seed)?;
+            return Ok(Box::new(board));
+        }
+        Ok(match self.engine {
+            Engine::Dense => Box::new(Board::new(self.bounds(), rule).with_topology(self.topology)),
+            Engine::Packed => {
+                Box::new(PackedBoard::new(self.bounds(), rule)?.with_topology(self.topology))
+            }
+            Engine::Sparse => Box::new(SparseUniverse::new(rule)?),
+            Engine::HashLife => Box::new(HashLife::new(rule)?),
+            Engine::Chunked => Box::new(ChunkedUniverse::new(rule)?),
+        })
+    }
+
+    fn build_second_order(&self, rule: Rule) -> Result<Box<dyn Universe>, RuleError> {
+        if self.engine != Engine::Dense {
+            return Err(RuleError::Unsupported(
+                "second-order rules only run on the Dense engine".to_string(),
+            ));
+        }
+        let rule = self.life_rule(rule)?;
+        Ok(Box::new(
+            SecondOrderBoard::new(self.bounds(), rule)?.with_topology(self.topology),
+        ))
+    }
+
+    fn build_larger_than_life(&self, rule: LargerThanLife) -> Result<Box<dyn Universe>, RuleError> {
+        if self.engine != Engine::Dense {
+            return Err(RuleError::Unsupported(
+                "Larger than Life rules only run on the Dense engine".to_string(),
+            ));
+        }
+        if self.neighborhood.is_some() {
+            return Err(RuleError::Unsupported(
+                "Larger than Life rules have their own neighborhood".to_string(),
+            ));
+        }
+        Ok(Box::new(
+            LtlBoard::new(self.bounds(), rule).with_topology(self.topology),
+        ))
+    }
+
+    fn build_cyclic(&self, rule: Cyclic, seed: u64) -> Result<Box<dyn Universe>, RuleError> {
+        if self.engine != Engine::Dense {
+            return Err(RuleError::Unsupported(
+                "cyclic rules only run on the Dense engine".to_string(),
+            ));
+        }
+        if self.neighborhood.is_some() {
+            return Err(RuleError::Unsupported(
+                "cyclic rules have their own neighborhood".to_string(),
+            ));
+        }
+        let mut universe = CyclicBoard::new(self.bounds(), rule)
+            .with_topology(self.topology)
+            .with_random_states(seed);
+        for (y, row) in self.board.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate().filter(|(_, cell)| **cell > 0) {
+                let state = Automaton::Cyclic(rule).cell_state(*cell);
+                universe.set(x as i32, y as i32, state);
+            }
+        }
+        Ok(Box::new(universe))
+    }
+
+    fn build_wolfram(&self, rule: Wolfram) -> Result<Box<dyn Universe>, RuleError> {
+        if self.engine != Engine::Dense {
+            return Err(RuleError::Unsupported(
+                "one-dimensional rules only run on the Dense engine".to_string(),
+            ));
+        }
+        if self.neighborhood.is_some() {
+            return Err(RuleError::Unsupported(
+                "one-dimensional rules have their own neighborhood".to_string(),
+            ));
+        }
+        let mut universe = WolframBoard::new(self.bounds(), rule).with_topology(self.topology);
+        for (x, cell) in self.board.first().into_iter().flatten().enumerate() {
+            universe.set(x as i32, self.max_y, CellState::from(*cell));
+        }
+        Ok(Box::new(universe))
+    }
+
+    fn build_margolus(&self, rule: Margolus) -> Result<Box<dyn Universe>, RuleError> {
+        if self.engine != Engine::Dense {
+            return Err(RuleError::Unsupported(
+                "Margolus rules only run on the Dense engine".to_string(),
+            ));
+        }
+        if self.neighborhood.is_some() {
+            return Err(RuleError::Unsupported(
+                "Margolus rules always use 2x2 blocks".to_string(),
+            ));
+        }
+        Ok(Box::new(
+            MargolusBoard::new(self.bounds(), rule).with_topology(self.topology)?,
+        ))
+    }
+
+    fn build_wireworld(&self) -> Result<Box<dyn Universe>, RuleError> {
+        if self.engine != Engine::Dense {
+            return Err(RuleError::Unsupported(
+                "Wireworld only runs on the Dense engine".to_string(),
+            ));
+        }
+        if self.neighborhood.is_some() {
+            return Err(RuleError::Unsupported(
+                "Wireworld always uses the Moore neighborhood".to_string(),
+            ));
+        }
+        Ok(Box::new(
+            WireworldBoard::new(self.bounds()).with_topology(self.topology),
+        ))
+    }
+
+    fn build_lenia(&self) -> Result<Box<dyn Universe>, RuleError> {
+        if self.engine != Engine::Dense {
+            return Err(RuleError::Unsupported(
+                "Lenia only runs on the Dense engine".to_string(),
+            ));
+        }
+        if self.neighborhood.is_some() {
+            return Err(RuleError::Unsupported(
+                "Lenia has its own kernel instead of a neighborhood".to_string(),
+            ));
+        }
+        let mut universe = LeniaBoard::new(self.bounds(), self.lenia)?.with_topology(self.topology);
+        for (y, row) in self.board.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                universe.set_level(x as i32, y as i32, *cell as f32 / 10.0);
+            }
+        }
+        Ok(Box::new(universe))
+    }
+
+    fn build_custom(&self, rule: CustomRule) -> Result<Box<dyn Universe>, RuleError> {
+        if self.engine != Engine::Dense {
+            return Err(RuleError::Unsupported(
+                "custom rules only run on the Dense engine".to_string(),
+            ));
+        }
+        if self.neighborhood.is_some() {
+            return Err(RuleError::Unsupported(
+                "custom rules have their own neighborhood".to_string(),
+            ));
+        }
+        Ok(Box::new(
+            CustomBoard::new(self.bounds(), rule).with_topology(self.topology),
+        ))
+    }
+
+    fn build_static(&self) -> Result<Box<dyn Universe>, RuleError> {
+        if self.engine != Engine::Dense {
+            return Err(RuleError::Unsupported(
+                "static boards only run on the Dense engine".to_string(),
+            ));
+        }
+        Ok(Box::new(StaticBoard::new(self.bounds())))
+    }
 }
 
 impl Default for BoardConfig {
     fn default() -> Self {
         Self {
             tile_size: 16.0,
+            engine: Engine::default(),
             min_x: -20,
             max_x: 20,
             min_y: -20,
             max_y: 20,
+            topology: Topology::default(),
+            neighborhood: None,
+            lenia: Lenia::default(),
             board: Vec::new(),
+            turmites: Vec::new(),
         }
     }
 }
@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::random::Rng;
+use crate::rule::decay;
+use crate::{CellState, Rule, RuleError};
+
+/// Probabilities that make a Life-like [`Rule`] random.
+///
+/// A number of alive neighbors listed in `birth` (`survival`) causes a birth
+/// (survival) with the given probability, all other numbers follow the rule.
+/// For example `B3/S23` with `survival: {2: 0.95}` is Conway's Game of Life
+/// where cells with 2 neighbors die 5% of the time.
+///
+/// After every step each cell flips between dead and alive with the
+/// probability `noise`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Stochastic {
+    #[serde(default)]
+    pub birth: BTreeMap<u8, f32>,
+    #[serde(default)]
+    pub survival: BTreeMap<u8, f32>,
+    #[serde(default)]
+    pub noise: f32,
+}
+
+impl Stochastic {
+    /// Fails if a probability isn't between 0 and 1 or a number of neighbors
+    /// is larger than the neighborhood of the rule.
+    pub fn validate(&self, rule: &Rule) -> Result<(), RuleError> {
+        let max = rule.neighborhood().size();
+        let counts = self.birth.iter().chain(&self.survival);
+        for (&count, &probability) in counts {
+            if count as usize > max {
+                return Err(RuleError::CountOutOfRange(count.into(), max));
+            }
+            if !(0.0..=1.0).contains(&probability) {
+                return Err(RuleError::Unsupported(
+                    "probabilities must be between 0 and 1".to_string(),
+                ));
+            }
+        }
+        if !(0.0..=1.0).contains(&self.noise) {
+            return Err(RuleError::Unsupported(
+                "the noise must be between 0 and 1".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// [`Stochastic`] probabilities and the random numbers drawn for them.
+#[derive(Debug, Clone)]
+pub(crate) struct Random {
+    stochastic: Stochastic,
+    rng: Rng,
+}
+
+impl Random {
+    pub(crate) fn new(stochastic: Stochastic, seed: u64) -> Self {
+        Self {
+            stochastic,
+            rng: Rng::new(seed),
+        }
+    }
+
+    /// Replaces `next`, the state `rule` gives a cell in `state`, if the
+    /// probabilities cover its number of alive neighbors.
+    pub(crate) fn next_state(
+        &mut self,
+        rule: &Rule,
+        state: CellState,
+        alive_neighbors: usize,
+        next: CellState,
+    ) -> CellState {
+        let count = alive_neighbors as u8;
+        match state {
+            CellState::Dead => match self.stochastic.birth.get(&count) {
+                Some(&p) if self.rng.chance(p) => CellState::Alive(0),
+                Some(_) => CellState::Dead,
+                None => next,
+            },
+            CellState::Alive(_) => match self.stochastic.survival.get(&count) {
+                Some(&p) if self.rng.chance(p) => state,
+                Some(_) => decay(rule.states(), 0),
+                None => next,
+            },
+            _ => next,
+        }
+    }
+
+    /// Flips cells between dead and alive with the noise probability.
+    pub(crate) fn add_noise(&mut self, cells: &mut [CellState]) {
+        if self.stochastic.noise <= 0.0 {
+            return;
+        }
+        for cell in cells {
+            if self.rng.chance(self.stochastic.noise) {
+                *cell = match cell {
+                    CellState::Alive(_) => CellState::Dead,
+                    _ => CellState::Alive(0),
+                };
+            }
+        }
+    }
+}
@@ -1,101 +1,190 @@
-use std::sync::mpsc::channel;
+use std::f32::consts::PI;
 
+use amethyst::core::transform::Transform;
 use amethyst::core::SystemBundle;
 use amethyst::core::Time;
 use amethyst::derive::SystemDesc;
 use amethyst::ecs::prelude::*;
 use amethyst::error::Error;
+use amethyst::renderer::palette::Srgba;
+use amethyst::renderer::resources::Tint;
 use amethyst::renderer::SpriteRender;
+use nalgebra::base::Vector3;
 
-use super::RunConfig;
+use game_of_live::{
+    Automaton, CellRule, CellState, CustomRule, Grid, RuleError, RuleRegistry, Universe,
+};
 
+use super::{RunConfig, TurmiteSystem, VisibleCellSystem};
+
+/// Steps the [`Universe`] resource and mirrors its cells into sprites.
+///
+/// Does nothing until the universe was inserted by the `GameState`.
 #[derive(Debug, Default, SystemDesc)]
 pub struct CellSystem {
     timer: f32,
+    // generation and edits the cells were last updated for
+    last_update: (u64, u64),
 }
 
 impl<'a> System<'a> for CellSystem {
     type SystemData = (
-        Entities<'a>,
+        Option<WriteExpect<'a, Box<dyn Universe>>>,
         WriteStorage<'a, Cell>,
-        ReadStorage<'a, Neighbors>,
         WriteStorage<'a, SpriteRender>,
+        WriteStorage<'a, Tint>,
         ReadExpect<'a, Time>,
         Read<'a, RunConfig>,
+        Read<'a, Edits>,
     );
 
     fn run(
         &mut self,
         (
-            entities,
+            universe,
             mut cell_storage,
-            neighbors_storage,
             mut sprite_render_storage,
+            mut tint_storage,
             time,
             run_config,
+            edits,
         ): Self::SystemData,
     ) {
-        if run_config.paused {
-            return;
+        let mut universe = match universe {
+            Some(universe) => universe,
+            None => return,
+        };
+
+        if !run_config.paused {
+            self.timer += time.delta_seconds();
+
+            if self.timer > run_config.speed {
+                self.timer = 0.0;
+                universe.step();
+            }
         }
 
-        self.timer += time.delta_seconds();
+        // also catches jumps and edits done by the `ControlSystem`
+        if (universe.generation(), edits.count) == self.last_update {
+            return;
+        }
+        self.last_update = (universe.generation(), edits.count);
+
+        let universe = &**universe;
+        let rule = &run_config.rule;
+        let grid = rule.grid();
+        if rule.is_continuous() {
+            // levels change without changing the state, so every cell is
+            // tinted again
+            (&cell_storage, &mut tint_storage)
+                .par_join()
+                .for_each(|(cell, tint)| *tint = level_tint(universe.level(cell.x, cell.y)));
+            return;
+        }
+        (
+            &mut cell_storage,
+            &mut sprite_render_storage,
+            &mut tint_storage,
+        )
+            .par_join()
+            .for_each(|(cell, sprite_render, tint)| {
+                let state = universe.get(cell.x, cell.y);
+                if cell.state != state {
+                    cell.state = state;
+                    sprite_render.sprite_number = sprite_number(state, grid);
+                    *tint = cell_tint(state, rule);
+                }
+            });
+    }
+}
 
-        if self.timer > run_config.speed {
-            self.timer = 0.0;
+/// Index into the sprite sheet used to display a cell in the given state.
+///
+/// Cells on a triangular grid use triangles pointing up, the transform
+/// turns them around for cells pointing down.
+pub fn sprite_number(state: CellState, grid: Grid) -> usize {
+    match (state, grid) {
+        (CellState::Alive(_), Grid::Triangular) => 3,
+        (CellState::Alive(_), _) => 0,
+        (CellState::Dead, _) => 1,
+        (CellState::Dying(_), Grid::Triangular) => 4,
+        (CellState::Dying(_), _) => 2,
+        // Wireworld cells are told apart by their tint
+        (CellState::Conductor | CellState::ElectronHead | CellState::ElectronTail, _) => 0,
+    }
+}
 
-            // iterate over all cells in parallel and use channels to collect
-            // which cells to kill or revive
-            let (kill_sender, kill_receiver) = channel();
-            let (revive_sender, revive_receiver) = channel();
+/// Transform of the cell at `(x, y)` relative to the `ScreenParent`.
+pub fn cell_transform(x: i32, y: i32, tile_size: f32, grid: Grid) -> Transform {
+    let column = match grid {
+        Grid::Square => x as f32,
+        // odd rows are shifted by half a cell, so every cell touches two
+        // cells in the rows above and below like a hexagon
+        Grid::Hexagonal => x as f32 + 0.5 * y.rem_euclid(2) as f32,
+        // neighboring triangles overlap by half their width
+        Grid::Triangular => x as f32 * 0.5,
+    };
+    let mut transform = Transform::default();
+    transform.set_translation_xyz(column * tile_size, y as f32 * tile_size, 0.0);
+    if grid == Grid::Triangular && !grid.points_up(x, y) {
+        transform.set_rotation_2d(PI);
+    }
+    // sprites are 8x8 pixels
+    transform.set_scale(Vector3::new(tile_size / 8.0, tile_size / 8.0, 1.0));
+    transform
+}
 
-            (&entities, &cell_storage, &neighbors_storage)
-                .par_join()
-                .for_each_with(
-                    (kill_sender, revive_sender),
-                    |(kill, revive), (entity, cell, neighbors)| {
-                        let alive_neighbors = neighbors.get_num_alive(&cell_storage);
-                        if cell.state == CellState::Dead {
-                            // dead cell
-                            if alive_neighbors == 3 {
-                                revive.send(entity).unwrap();
-                            }
-                        } else {
-                            // alive cell
-                            if alive_neighbors < 2 || alive_neighbors > 3 {
-                                kill.send(entity).unwrap();
-                            }
-                        }
-                    },
-                );
-
-            let kill_cells: Vec<_> = kill_receiver.iter().collect();
-            let revive_cells: Vec<_> = revive_receiver.iter().collect();
-
-            for entity in kill_cells {
-                cell_storage
-                    .get_mut(entity)
-                    .map(|c| c.state = CellState::Dead);
-                // ui_text.get_mut(entity).map(|t| t.text = "-".to_string());
-                // hidden_storage.insert(entity, Hidden);
-                sprite_render_storage
-                    .get_mut(entity)
-                    .map(|s| s.sprite_number = 1);
-            }
-            for entity in revive_cells {
-                cell_storage
-                    .get_mut(entity)
-                    .map(|c| c.state = CellState::Alive);
-                // ui_text.get_mut(entity).map(|t| t.text = "#".to_string());
-                // hidden_storage.remove(entity);
-                sprite_render_storage
-                    .get_mut(entity)
-                    .map(|s| s.sprite_number = 0);
-            }
+/// Tint of a cell in the given state, dying cells fade out with age.
+///
+/// Wireworld cells use Golly's colors, the colors of multi-color rules are
+/// spread evenly around the color wheel.
+pub fn cell_tint(state: CellState, rule: &Automaton) -> Tint {
+    let brightness = match state {
+        CellState::Alive(color) if rule.colors() > 1 => {
+            let (r, g, b) = hue(color as f32 / rule.colors() as f32);
+            return Tint(Srgba::new(r, g, b, 1.0));
         }
+        CellState::Conductor => return Tint(Srgba::new(1.0, 0.5, 0.0, 1.0)),
+        CellState::ElectronHead => return Tint(Srgba::new(0.0, 0.5, 1.0, 1.0)),
+        CellState::ElectronTail => return Tint(Srgba::new(1.0, 1.0, 1.0, 1.0)),
+        CellState::Dying(age) => 0.5 * (1.0 - age as f32 / (rule.states() - 1) as f32),
+        _ => 0.5,
+    };
+    Tint(Srgba::new(brightness, brightness, brightness, 1.0))
+}
+
+/// Fully saturated color at the given fraction of the way around the color
+/// wheel, starting at red.
+fn hue(fraction: f32) -> (f32, f32, f32) {
+    let sector = fraction.rem_euclid(1.0) * 6.0;
+    let x = 1.0 - (sector % 2.0 - 1.0).abs();
+    match sector as u32 {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
     }
 }
 
+/// Tint of a cell of a continuous rule, brighter the higher its level.
+///
+/// Such cells always use the sprite of an alive cell.
+pub fn level_tint(level: f32) -> Tint {
+    Tint(Srgba::new(level, level, level, 1.0))
+}
+
+/// Number of cells edited by hand so far.
+///
+/// Edits don't step the [`Universe`], so this tells the systems mirroring it
+/// that the cells changed anyway.
+#[derive(Debug, Default)]
+pub struct Edits {
+    pub count: u64,
+}
+
+/// Presentation of a single cell of the [`Universe`] resource.
 #[derive(Debug, PartialEq, Eq)]
 pub struct Cell {
     pub x: i32,
@@ -107,56 +196,35 @@ impl Component for Cell {
     type Storage = VecStorage<Self>;
 }
 
-#[derive(Debug, PartialEq, Eq)]
-pub enum CellState {
-    Alive,
-    Dead,
-}
-
-#[derive(Default, Debug, PartialEq, Eq)]
-pub struct Neighbors {
-    pub n: Option<Entity>,
-    pub ne: Option<Entity>,
-    pub e: Option<Entity>,
-    pub se: Option<Entity>,
-    pub s: Option<Entity>,
-    pub sw: Option<Entity>,
-    pub w: Option<Entity>,
-    pub nw: Option<Entity>,
-}
-
-impl Component for Neighbors {
-    type Storage = VecStorage<Self>;
+/// Systems of the cells and the rules that can be selected by name in the
+/// `RunConfig`, `ConwayLife` and the registered ones.
+#[derive(Default, Debug)]
+pub struct CellBundle {
+    rules: RuleRegistry,
 }
 
-impl Neighbors {
-    fn get_num_alive<'a>(&self, cell_storage: &WriteStorage<'a, Cell>) -> usize {
-        return [
-            self.n, self.ne, self.e, self.se, self.s, self.sw, self.w, self.nw,
-        ]
-        .iter()
-        .map(|n| n.as_ref().and_then(|e| cell_storage.get(*e)))
-        .filter(|c| match *c {
-            Some(Cell {
-                state: CellState::Alive,
-                ..
-            }) => true,
-            _ => false,
-        })
-        .count();
+impl CellBundle {
+    /// Registers a rule the `RunConfig` can select by name, like the rules
+    /// of plugins. Fails if the name is taken.
+    pub fn register(&mut self, rule: impl CellRule + 'static) -> Result<CustomRule, RuleError> {
+        self.rules.register(rule)
     }
 }
 
-#[derive(Default, Debug)]
-pub struct CellBundle;
-
 impl<'a, 'b> SystemBundle<'a, 'b> for CellBundle {
     fn build(
         self,
-        _world: &mut World,
+        world: &mut World,
         builder: &mut DispatcherBuilder<'a, 'b>,
     ) -> Result<(), Error> {
+        world.insert(self.rules);
         builder.add(CellSystem::default(), "cell_system", &[]);
+        builder.add(TurmiteSystem::default(), "turmite_system", &["cell_system"]);
+        builder.add(
+            VisibleCellSystem::default(),
+            "visible_cell_system",
+            &["cell_system", "turmite_system"],
+        );
         Ok(())
     }
 }
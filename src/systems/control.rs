@@ -7,15 +7,20 @@ use amethyst::derive::SystemDesc;
 use amethyst::ecs::prelude::*;
 use amethyst::error::Error;
 use amethyst::input::InputEvent;
+use amethyst::input::InputHandler;
 use amethyst::input::StringBindings;
 use amethyst::prelude::*;
 use amethyst::shrev::EventChannel;
 use amethyst::shrev::ReaderId;
+use amethyst::window::ScreenDimensions;
 use nalgebra::base::Vector3;
 
 use log::{debug, info};
 
-use super::ScreenParent;
+use game_of_live::{Automaton, CellState, RuleError, RuleRegistry, Stochastic, Universe};
+
+use super::{cell_at, Edits, ScreenParent};
+use crate::states::game::BoardConfig;
 use crate::utils;
 
 #[derive(SystemDesc)]
@@ -30,11 +35,27 @@ impl<'a> System<'a> for ControlSystem {
         Read<'a, EventChannel<InputEvent<StringBindings>>>,
         ReadStorage<'a, ScreenParent>,
         WriteStorage<'a, Transform>,
+        Option<WriteExpect<'a, Box<dyn Universe>>>,
+        Read<'a, InputHandler<StringBindings>>,
+        ReadExpect<'a, ScreenDimensions>,
+        ReadExpect<'a, BoardConfig>,
+        Write<'a, Edits>,
     );
 
     fn run(
         &mut self,
-        (mut run_config, mut ui_config, event_channel, camera_storage, mut transform_storage): Self::SystemData,
+        (
+            mut run_config,
+            mut ui_config,
+            event_channel,
+            camera_storage,
+            mut transform_storage,
+            mut universe,
+            input,
+            screen,
+            board,
+            mut edits,
+        ): Self::SystemData,
     ) {
         for event in event_channel.read(&mut self.event_reader) {
             if let InputEvent::ActionPressed(action) = event {
@@ -47,6 +68,36 @@ impl<'a> System<'a> for ControlSystem {
                 } else if action == "toggle_pause" {
                     run_config.paused = !run_config.paused;
                     debug!("Toggle Pause ({})", run_config.paused);
+                } else if action == "reverse" {
+                    if let Some(universe) = universe.as_mut() {
+                        if universe.reverse() {
+                            debug!("Reverse direction");
+                        } else {
+                            info!("{} can't run backwards", run_config.rule);
+                        }
+                    }
+                } else if action == "jump" {
+                    if let Some(universe) = universe.as_mut() {
+                        universe.jump(run_config.jump_exponent);
+                        debug!(
+                            "Jump 2^{} generations (now {})",
+                            run_config.jump_exponent,
+                            universe.generation()
+                        );
+                    }
+                } else if action == "edit_cell" || action == "spark" {
+                    let parent = (&camera_storage, &transform_storage).join().next();
+                    if let (Some(universe), Some((_, parent)), Some(mouse)) =
+                        (universe.as_mut(), parent, input.mouse_position())
+                    {
+                        let grid = run_config.rule.grid();
+                        let (x, y) = cell_at(parent, &screen, board.tile_size(), grid, mouse);
+                        let spark = action == "spark";
+                        let state = edited_state(&run_config.rule, universe.get(x, y), spark);
+                        universe.set(x, y, state);
+                        edits.count += 1;
+                        debug!("Edit cell ({}, {}) to {:?}", x, y, state);
+                    }
                 } else if action == "toggle_fps" {
                     ui_config.show_fps = !ui_config.show_fps;
                     debug!("Toggle fps ({})", ui_config.show_fps);
@@ -96,6 +147,28 @@ impl<'a> System<'a> for ControlSystem {
     }
 }
 
+/// State of a cell after it was clicked.
+///
+/// The edit action toggles cells between dead and alive, or a conductor for
+/// Wireworld, and cycles cells of multi-color rules through their colors
+/// before they are dead again. The spark action only works on Wireworld
+/// conductors and cycles them through an electron head and tail, which sets
+/// the direction the electron moves in.
+fn edited_state(rule: &Automaton, state: CellState, spark: bool) -> CellState {
+    match (rule, state) {
+        (_, CellState::Alive(color)) if !spark && color + 1 < rule.colors() => {
+            CellState::Alive(color + 1)
+        }
+        (Automaton::Wireworld, CellState::Conductor) if spark => CellState::ElectronHead,
+        (Automaton::Wireworld, CellState::ElectronHead) if spark => CellState::ElectronTail,
+        (Automaton::Wireworld, CellState::ElectronTail) if spark => CellState::Conductor,
+        (_, state) if spark => state,
+        (Automaton::Wireworld, CellState::Dead) => CellState::Conductor,
+        (_, CellState::Dead) => CellState::Alive(0),
+        _ => CellState::Dead,
+    }
+}
+
 #[derive(Default, Debug)]
 pub struct ControlBundle {
     config_path: PathBuf,
@@ -123,9 +196,17 @@ impl<'a, 'b> SystemBundle<'a, 'b> for ControlBundle {
 
         let system = ControlSystem { event_reader };
 
-        world.insert(utils::load_config::<RunConfig>(
-            &self.config_path.join("run.ron"),
-        ));
+        // No fallback to the default here, an invalid rule should stop the
+        // game instead of silently running Conway's rule.
+        let run_config = RunConfig::load(&self.config_path.join("run.ron"))?;
+        let max = world.read_resource::<BoardConfig>().max_jump_exponent();
+        if run_config.jump_exponent > max {
+            return Err(Error::from_string(format!(
+                "jump_exponent {} is too large for the engine, must be at most {}",
+                run_config.jump_exponent, max
+            )));
+        }
+        world.insert(run_config);
         world.insert(utils::load_config::<UiConfig>(
             &self.config_path.join("ui.ron"),
         ));
@@ -140,6 +221,40 @@ pub struct RunConfig {
     pub paused: bool,
     /// Delay between cell simulation update (in seconds).
     pub speed: f32,
+    /// Life-like rule in B/S notation (e.g. `"B36/S23"`) or a rule of another
+    /// family in its usual notation, `"B3/S23"` if left out.
+    #[serde(default)]
+    pub rule: Automaton,
+    /// Birth and survival probabilities and noise that make a Life-like rule
+    /// random.
+    #[serde(default)]
+    pub stochastic: Option<Stochastic>,
+    /// Seed of the random numbers of stochastic rules and the random start
+    /// of cyclic rules, the same seed always gives the same run.
+    #[serde(default)]
+    pub seed: u64,
+    /// The jump action advances `2^jump_exponent` generations at once, up
+    /// to the limit of the engine in `BoardConfig::max_jump_exponent`.
+    #[serde(default)]
+    pub jump_exponent: u32,
+    /// Name of a rule registered with the `CellBundle` or loaded from the
+    /// `plugins` directory, runs instead of `rule` if set.
+    #[serde(default)]
+    pub custom_rule: Option<String>,
+}
+
+impl RunConfig {
+    /// Replaces `rule` by the registered rule named by `custom_rule`, the
+    /// only way to run a custom rule.
+    pub fn select_rule(&mut self, rules: &RuleRegistry) -> Result<(), RuleError> {
+        if let Some(name) = &self.custom_rule {
+            let rule = rules
+                .get(name)
+                .ok_or_else(|| RuleError::UnknownRule(name.clone()))?;
+            self.rule = Automaton::Custom(rule);
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, PartialEq, Default, Deserialize, Serialize)]
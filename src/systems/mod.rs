@@ -2,8 +2,12 @@ mod cell;
 mod control;
 mod drag_world;
 mod fps_display;
+mod turmite;
+mod visible_cells;
 
 pub use cell::*;
 pub use control::*;
 pub use drag_world::*;
 pub use fps_display::*;
+pub use turmite::*;
+pub use visible_cells::*;
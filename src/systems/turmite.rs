@@ -0,0 +1,99 @@
+use std::f32::consts::PI;
+
+use amethyst::core::transform::Transform;
+use amethyst::derive::SystemDesc;
+use amethyst::ecs::prelude::*;
+
+use game_of_live::{Direction, Grid, Turmite, TurmiteRule, Universe};
+
+use super::{cell_transform, Edits};
+use crate::states::game::BoardConfig;
+
+/// Index of the turmite sprite, an arrow pointing up.
+pub const TURMITE_SPRITE: usize = 5;
+
+// most steps a turmite takes in one frame, so jumping far ahead doesn't
+// freeze the game, the rest of a jump is caught up in the following frames
+const MAX_STEPS: u64 = 1 << 16;
+
+/// Moves the [`TurmiteAgent`]s one step for every generation the
+/// [`Universe`] resource advanced, at most `MAX_STEPS` per frame.
+#[derive(Debug, Default, SystemDesc)]
+pub struct TurmiteSystem {
+    // generation the turmites have caught up to
+    generation: Option<u64>,
+}
+
+impl<'a> System<'a> for TurmiteSystem {
+    type SystemData = (
+        Option<WriteExpect<'a, Box<dyn Universe>>>,
+        WriteStorage<'a, TurmiteAgent>,
+        WriteStorage<'a, Transform>,
+        ReadExpect<'a, BoardConfig>,
+        Write<'a, Edits>,
+    );
+
+    fn run(
+        &mut self,
+        (universe, mut turmite_storage, mut transform_storage, board, mut edits): Self::SystemData,
+    ) {
+        let mut universe = match universe {
+            Some(universe) => universe,
+            None => return,
+        };
+        let generation = universe.generation();
+        let last = match self.generation {
+            // a new universe may start at an earlier generation
+            Some(last) if last <= generation => last,
+            _ => {
+                self.generation = Some(generation);
+                return;
+            }
+        };
+        let steps = (generation - last).min(MAX_STEPS);
+        if steps == 0 {
+            return;
+        }
+        // stays behind the universe until the turmites took every step
+        self.generation = Some(last + steps);
+
+        let mut moved = false;
+        for (agent, transform) in (&mut turmite_storage, &mut transform_storage).join() {
+            for _ in 0..steps {
+                agent.turmite.step(&agent.rule, &mut **universe);
+            }
+            *transform = turmite_transform(&agent.turmite, board.tile_size());
+            moved = true;
+        }
+        // the cells the turmites wrote have to be mirrored again
+        if moved {
+            edits.count += 1;
+        }
+    }
+}
+
+/// Transform of a turmite relative to the `ScreenParent`, drawn above the
+/// cell it is on and rotated towards the direction it faces.
+pub fn turmite_transform(turmite: &Turmite, tile_size: f32) -> Transform {
+    let mut transform = cell_transform(turmite.x, turmite.y, tile_size, Grid::Square);
+    transform.set_translation_z(0.5);
+    let angle = match turmite.direction {
+        Direction::Up => 0.0,
+        Direction::Right => -PI / 2.0,
+        Direction::Down => PI,
+        Direction::Left => PI / 2.0,
+    };
+    transform.set_rotation_2d(angle);
+    transform
+}
+
+/// Turmite walking over the cells of the [`Universe`] resource.
+#[derive(Debug)]
+pub struct TurmiteAgent {
+    pub turmite: Turmite,
+    pub rule: TurmiteRule,
+}
+
+impl Component for TurmiteAgent {
+    type Storage = DenseVecStorage<Self>;
+}
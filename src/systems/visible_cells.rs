@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use amethyst::assets::Handle;
+use amethyst::core::transform::Parent;
+use amethyst::core::transform::Transform;
+use amethyst::derive::SystemDesc;
+use amethyst::ecs::prelude::*;
+use amethyst::renderer::resources::Tint;
+use amethyst::renderer::SpriteRender;
+use amethyst::renderer::SpriteSheet;
+use amethyst::window::ScreenDimensions;
+
+use game_of_live::{Bounds, Grid, Universe};
+
+use super::{cell_tint, cell_transform, sprite_number, Cell, Edits, RunConfig};
+use crate::states::loading::{INTERNAL_HEIGHT, INTERNAL_WIDTH};
+
+/// Cells that currently have an entity if the universe is too large to have
+/// an entity for every cell.
+///
+/// Only inserted by the `GameState` for such universes.
+pub struct VisibleCells {
+    sprite_sheet: Handle<SpriteSheet>,
+    parent: Entity,
+    tile_size: f32,
+    entities: HashMap<(i32, i32), Entity>,
+}
+
+impl VisibleCells {
+    pub fn new(sprite_sheet: Handle<SpriteSheet>, parent: Entity, tile_size: f32) -> Self {
+        Self {
+            sprite_sheet,
+            parent,
+            tile_size,
+            entities: HashMap::new(),
+        }
+    }
+}
+
+/// Spawns entities for the cells of a large universe that are not dead and
+/// visible on screen, and deletes them once they die or leave the screen.
+///
+/// Dead cells have no entity at all so the number of entities only depends
+/// on the visible population.
+#[derive(Debug, Default, SystemDesc)]
+pub struct VisibleCellSystem {
+    // generation, edits and area of the last update
+    last_update: Option<(u64, u64, Bounds)>,
+}
+
+impl<'a> System<'a> for VisibleCellSystem {
+    type SystemData = (
+        Option<WriteExpect<'a, VisibleCells>>,
+        Option<ReadExpect<'a, Box<dyn Universe>>>,
+        Entities<'a>,
+        WriteStorage<'a, Cell>,
+        WriteStorage<'a, Parent>,
+        WriteStorage<'a, Transform>,
+        WriteStorage<'a, SpriteRender>,
+        WriteStorage<'a, Tint>,
+        ReadExpect<'a, ScreenDimensions>,
+        Read<'a, RunConfig>,
+        Read<'a, Edits>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            visible,
+            universe,
+            entities,
+            mut cell_storage,
+            mut parent_storage,
+            mut transform_storage,
+            mut sprite_render_storage,
+            mut tint_storage,
+            screen,
+            run_config,
+            edits,
+        ): Self::SystemData,
+    ) {
+        let (mut visible, universe) = match (visible, universe) {
+            (Some(visible), Some(universe)) => (visible, universe),
+            _ => return,
+        };
+
+        let area = match transform_storage.get(visible.parent) {
+            Some(parent_transform) => visible_area(
+                parent_transform,
+                &screen,
+                visible.tile_size,
+                run_config.rule.grid(),
+            ),
+            None => return,
+        };
+        let update = (universe.generation(), edits.count, area);
+        if self.last_update == Some(update) {
+            return;
+        }
+        self.last_update = Some(update);
+
+        let rule = &run_config.rule;
+        let grid = rule.grid();
+        let mut seen = HashSet::new();
+        for (x, y, state) in universe.live_cells(&area) {
+            seen.insert((x, y));
+            if let Some(entity) = visible.entities.get(&(x, y)) {
+                if let Some(cell) = cell_storage.get_mut(*entity) {
+                    if cell.state != state {
+                        cell.state = state;
+                        if let Some(sprite_render) = sprite_render_storage.get_mut(*entity) {
+                            sprite_render.sprite_number = sprite_number(state, grid);
+                        }
+                        if let Some(tint) = tint_storage.get_mut(*entity) {
+                            *tint = cell_tint(state, rule);
+                        }
+                    }
+                }
+                continue;
+            }
+
+            let entity = entities
+                .build_entity()
+                .with(Cell { x, y, state }, &mut cell_storage)
+                .with(Parent::new(visible.parent), &mut parent_storage)
+                .with(
+                    cell_transform(x, y, visible.tile_size, grid),
+                    &mut transform_storage,
+                )
+                .with(
+                    SpriteRender {
+                        sprite_sheet: visible.sprite_sheet.clone(),
+                        sprite_number: sprite_number(state, grid),
+                    },
+                    &mut sprite_render_storage,
+                )
+                .with(cell_tint(state, rule), &mut tint_storage)
+                .build();
+            visible.entities.insert((x, y), entity);
+        }
+
+        visible.entities.retain(|pos, entity| {
+            if seen.contains(pos) {
+                true
+            } else {
+                entities.delete(*entity).unwrap();
+                false
+            }
+        });
+    }
+}
+
+/// Cells that are (at least partially) visible with the camera fixed at the
+/// origin and the board moved and zoomed by the `ScreenParent` transform.
+fn visible_area(
+    parent_transform: &Transform,
+    screen: &ScreenDimensions,
+    tile_size: f32,
+    grid: Grid,
+) -> Bounds {
+    let (half_width, half_height) = half_view(screen);
+    let translation = parent_transform.translation();
+    let cell_size = parent_transform.scale()[0] * tile_size;
+    // triangles overlap by half their width
+    let column_width = match grid {
+        Grid::Triangular => cell_size / 2.0,
+        _ => cell_size,
+    };
+    let to_cell = |world: f32, offset: f32, size: f32| (world - offset) / size;
+
+    Bounds::new(
+        to_cell(-half_width, translation[0], column_width).floor() as i32 - 1,
+        to_cell(half_width, translation[0], column_width).ceil() as i32 + 1,
+        to_cell(-half_height, translation[1], cell_size).floor() as i32 - 1,
+        to_cell(half_height, translation[1], cell_size).ceil() as i32 + 1,
+    )
+}
+
+/// Cell under a point on the screen given in pixels from the top left corner,
+/// like the mouse position.
+pub fn cell_at(
+    parent_transform: &Transform,
+    screen: &ScreenDimensions,
+    tile_size: f32,
+    grid: Grid,
+    (screen_x, screen_y): (f32, f32),
+) -> (i32, i32) {
+    let (half_width, half_height) = half_view(screen);
+    let world_x = (screen_x / screen.width() * 2.0 - 1.0) * half_width;
+    let world_y = (1.0 - screen_y / screen.height() * 2.0) * half_height;
+
+    let translation = parent_transform.translation();
+    let cell_size = parent_transform.scale()[0] * tile_size;
+    let y = ((world_y - translation[1]) / cell_size).round();
+    let column = (world_x - translation[0]) / cell_size;
+    // undo the offsets of `cell_transform`
+    let x = match grid {
+        Grid::Square => column,
+        Grid::Hexagonal => column - 0.5 * (y as i32).rem_euclid(2) as f32,
+        Grid::Triangular => column * 2.0,
+    };
+    (x.round() as i32, y as i32)
+}
+
+/// Half the width and height of the world area shown by the camera.
+fn half_view(screen: &ScreenDimensions) -> (f32, f32) {
+    // the camera shows at least the internal area and more along one axis
+    // if the aspect ratio of the screen differs
+    let aspect = screen.width() / screen.height();
+    if aspect > INTERNAL_WIDTH / INTERNAL_HEIGHT {
+        (INTERNAL_HEIGHT * aspect / 2.0, INTERNAL_HEIGHT / 2.0)
+    } else {
+        (INTERNAL_WIDTH / 2.0, INTERNAL_WIDTH / aspect / 2.0)
+    }
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Bounds;
+
+/// How the edges of a finite board are connected.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Topology {
+    /// Everything outside of the board is dead.
+    #[default]
+    Bounded,
+    /// Opposite edges are connected.
+    Torus,
+    /// Left and right edges are connected, top and bottom are bounded.
+    Cylinder,
+    /// Like a torus but crossing the left or right edge flips the board
+    /// upside down.
+    KleinBottle,
+    /// Crossing any edge flips the board along the other axis (also known as
+    /// cross-surface).
+    ProjectivePlane,
+}
+
+/// How a pair of opposite edges is connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Edges {
+    /// Not connected, cells beyond are dead.
+    Closed,
+    /// Leaving on one edge enters on the opposite one.
+    Joined,
+    /// Like [`Edges::Joined`] but mirrored along the other axis.
+    Twisted,
+}
+
+impl Topology {
+    /// How the left/right and the top/bottom edges are connected.
+    pub fn edges(self) -> (Edges, Edges) {
+        match self {
+            Topology::Bounded => (Edges::Closed, Edges::Closed),
+            Topology::Torus => (Edges::Joined, Edges::Joined),
+            Topology::Cylinder => (Edges::Joined, Edges::Closed),
+            Topology::KleinBottle => (Edges::Twisted, Edges::Joined),
+            Topology::ProjectivePlane => (Edges::Twisted, Edges::Twisted),
+        }
+    }
+
+    /// Maps a coordinate that may lie outside of `bounds` to the cell it
+    /// refers to, or `None` if there is no such cell. Coordinates more than a
+    /// board away go around the surface more than once.
+    pub fn resolve(self, bounds: &Bounds, x: i32, y: i32) -> Option<(i32, i32)> {
+        let (horizontal, vertical) = self.edges();
+        let (mut x, mut y) = (x, y);
+
+        if x < bounds.min_x || x > bounds.max_x {
+            match horizontal {
+                Edges::Closed => return None,
+                Edges::Joined => x = wrap(x, bounds.min_x, bounds.max_x),
+                Edges::Twisted => {
+                    if laps(x, bounds.min_x, bounds.max_x) % 2 != 0 {
+                        y = bounds.min_y + bounds.max_y - y;
+                    }
+                    x = wrap(x, bounds.min_x, bounds.max_x);
+                }
+            }
+        }
+        if y < bounds.min_y || y > bounds.max_y {
+            match vertical {
+                Edges::Closed => return None,
+                Edges::Joined => y = wrap(y, bounds.min_y, bounds.max_y),
+                Edges::Twisted => {
+                    if laps(y, bounds.min_y, bounds.max_y) % 2 != 0 {
+                        x = bounds.min_x + bounds.max_x - x;
+                    }
+                    y = wrap(y, bounds.min_y, bounds.max_y);
+                }
+            }
+        }
+        Some((x, y))
+    }
+}
+
+fn wrap(v: i32, min: i32, max: i32) -> i32 {
+    min + (v - min).rem_euclid(max - min + 1)
+}
+
+/// How many times `v` went around `min..=max`, a twisted edge mirrors the
+/// other axis once per lap.
+fn laps(v: i32, min: i32, max: i32) -> i32 {
+    (v - min).div_euclid(max - min + 1)
+}
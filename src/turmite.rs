@@ -0,0 +1,392 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Bounds, CellState, RuleError, Universe, MAX_STATES};
+
+/// Direction a [`Turmite`] faces, where up is towards positive y.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Direction {
+    #[default]
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub fn offset(self) -> (i32, i32) {
+        match self {
+            Direction::Up => (0, 1),
+            Direction::Right => (1, 0),
+            Direction::Down => (0, -1),
+            Direction::Left => (-1, 0),
+        }
+    }
+
+    pub fn turn(self, turn: Turn) -> Self {
+        let quarters = match turn {
+            Turn::None => 0,
+            Turn::Right => 1,
+            Turn::UTurn => 2,
+            Turn::Left => 3,
+        };
+        Self::ALL[(self as usize + quarters) % 4]
+    }
+}
+
+/// Turn relative to the direction a [`Turmite`] faces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Turn {
+    None,
+    Right,
+    UTurn,
+    Left,
+}
+
+impl Turn {
+    // codes of the turns in Golly's notation
+    fn code(self) -> u32 {
+        match self {
+            Turn::None => 1,
+            Turn::Right => 2,
+            Turn::UTurn => 4,
+            Turn::Left => 8,
+        }
+    }
+
+    fn letter(self) -> char {
+        match self {
+            Turn::None => 'N',
+            Turn::Right => 'R',
+            Turn::UTurn => 'U',
+            Turn::Left => 'L',
+        }
+    }
+}
+
+/// What a [`Turmite`] does in a state on a cell of a color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Transition {
+    /// Color written to the cell.
+    pub write: u8,
+    pub turn: Turn,
+    pub next_state: u8,
+}
+
+/// Rule table of a [`Turmite`].
+///
+/// Written as a Langton's ant like `RL`, which turns right on color 0 and
+/// left on color 1 and advances the color of every cell it leaves. Each
+/// letter is a color and its turn: `L`eft, `R`ight, `N`one or `U`-turn.
+///
+/// Turmites with more than one state use Golly's notation
+/// `{{{write, turn, next state}, ...}, ...}` with a list of transitions per
+/// color for every state, where turns are `1` (none), `2` (right), `4`
+/// (U-turn) and `8` (left), e.g. `{{{1,2,0},{0,8,0}}}` for Langton's ant.
+///
+/// Colors are the state numbers used by Golly, see [`CellState::from`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TurmiteRule {
+    colors: u8,
+    // the transition of state s on color c is at s * colors + c
+    table: Vec<Transition>,
+}
+
+impl TurmiteRule {
+    pub fn colors(&self) -> u8 {
+        self.colors
+    }
+
+    pub fn states(&self) -> u8 {
+        (self.table.len() / self.colors as usize) as u8
+    }
+
+    pub fn transition(&self, state: u8, color: u8) -> Transition {
+        self.table[state as usize * self.colors as usize + color as usize]
+    }
+
+    /// Langton's ant (`RL`).
+    pub fn langtons_ant() -> Self {
+        "RL".parse().unwrap()
+    }
+
+    fn parse_ant(s: &str) -> Result<Self, RuleError> {
+        let turns = s
+            .chars()
+            .map(|c| match c.to_ascii_uppercase() {
+                'N' => Ok(Turn::None),
+                'R' => Ok(Turn::Right),
+                'U' => Ok(Turn::UTurn),
+                'L' => Ok(Turn::Left),
+                c => Err(RuleError::UnexpectedChar(c)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let colors = u8::try_from(turns.len())
+            .ok()
+            .filter(|&colors| colors > 0)
+            .ok_or_else(|| RuleError::Malformed(s.to_string()))?;
+        let table = turns
+            .iter()
+            .enumerate()
+            .map(|(color, &turn)| Transition {
+                write: ((color + 1) % turns.len()) as u8,
+                turn,
+                next_state: 0,
+            })
+            .collect();
+        Ok(Self { colors, table })
+    }
+
+    fn parse_golly(s: &str) -> Result<Self, RuleError> {
+        let malformed = || RuleError::Malformed(s.to_string());
+        let compact: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        let inner = compact
+            .strip_prefix("{{{")
+            .and_then(|inner| inner.strip_suffix("}}}"))
+            .ok_or_else(malformed)?;
+
+        let mut table = Vec::new();
+        let mut colors = None;
+        let mut states = 0;
+        for state in inner.split("}},{{") {
+            let mut count = 0;
+            for transition in state.split("},{") {
+                let numbers = transition
+                    .split(',')
+                    .map(|n| {
+                        if let Some(c) = n.chars().find(|c| !c.is_ascii_digit()) {
+                            return Err(RuleError::UnexpectedChar(c));
+                        }
+                        n.parse::<u32>().map_err(|_| malformed())
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let (write, turn, next_state) = match numbers[..] {
+                    [write, turn, next_state] => (write, turn, next_state),
+                    _ => return Err(malformed()),
+                };
+                let turn = [Turn::None, Turn::Right, Turn::UTurn, Turn::Left]
+                    .iter()
+                    .copied()
+                    .find(|t| t.code() == turn)
+                    .ok_or_else(malformed)?;
+                let write = u8::try_from(write).map_err(|_| malformed())?;
+                let next_state = u8::try_from(next_state).map_err(|_| malformed())?;
+                table.push(Transition {
+                    write,
+                    turn,
+                    next_state,
+                });
+                count += 1;
+            }
+            // every state needs a transition for every color
+            if *colors.get_or_insert(count) != count {
+                return Err(malformed());
+            }
+            states += 1;
+        }
+
+        let colors = colors.unwrap_or(0);
+        if colors > MAX_STATES as usize {
+            return Err(RuleError::StatesOutOfRange(colors as u32));
+        }
+        let colors = colors as u8;
+        let valid = table
+            .iter()
+            .all(|t| t.write < colors && (t.next_state as usize) < states);
+        if !valid || states > MAX_STATES as usize {
+            return Err(malformed());
+        }
+        Ok(Self { colors, table })
+    }
+
+    /// Whether this is a Langton's ant that can be written with letters.
+    fn is_ant(&self) -> bool {
+        self.states() == 1
+            && self
+                .table
+                .iter()
+                .enumerate()
+                .all(|(color, t)| t.write as usize == (color + 1) % self.colors as usize)
+    }
+}
+
+impl Default for TurmiteRule {
+    fn default() -> Self {
+        Self::langtons_ant()
+    }
+}
+
+impl FromStr for TurmiteRule {
+    type Err = RuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.starts_with('{') {
+            Self::parse_golly(s)
+        } else {
+            Self::parse_ant(s)
+        }
+    }
+}
+
+impl fmt::Display for TurmiteRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_ant() {
+            for t in &self.table {
+                write!(f, "{}", t.turn.letter())?;
+            }
+            return Ok(());
+        }
+        let states: Vec<String> = self
+            .table
+            .chunks(self.colors as usize)
+            .map(|transitions| {
+                let transitions: Vec<String> = transitions
+                    .iter()
+                    .map(|t| format!("{{{},{},{}}}", t.write, t.turn.code(), t.next_state))
+                    .collect();
+                format!("{{{}}}", transitions.join(","))
+            })
+            .collect();
+        write!(f, "{{{}}}", states.join(","))
+    }
+}
+
+impl TryFrom<String> for TurmiteRule {
+    type Error = RuleError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<TurmiteRule> for String {
+    fn from(rule: TurmiteRule) -> Self {
+        rule.to_string()
+    }
+}
+
+/// Agent that walks over a [`Universe`] and changes the cells it visits.
+///
+/// Turmites don't follow the topology of finite boards, outside of the
+/// bounds they only see dead cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Turmite {
+    pub x: i32,
+    pub y: i32,
+    pub direction: Direction,
+    pub state: u8,
+}
+
+impl Turmite {
+    pub fn new(x: i32, y: i32, direction: Direction) -> Self {
+        Self {
+            x,
+            y,
+            direction,
+            state: 0,
+        }
+    }
+
+    /// Writes the cell the turmite is on, then turns and moves one cell
+    /// forward.
+    ///
+    /// Cells of colors the rule doesn't know count as color 0.
+    pub fn step(&mut self, rule: &TurmiteRule, universe: &mut dyn Universe) {
+        let color = match universe.get(self.x, self.y) {
+            CellState::Dead => 0,
+            CellState::Alive(_) => 1,
+            CellState::Dying(age) => age.saturating_add(1),
+            _ => 0,
+        };
+        let color = if color < rule.colors { color } else { 0 };
+        let state = if self.state < rule.states() {
+            self.state
+        } else {
+            0
+        };
+
+        let transition = rule.transition(state, color);
+        universe.set(self.x, self.y, CellState::from(transition.write));
+        self.direction = self.direction.turn(transition.turn);
+        self.state = transition.next_state;
+        let (dx, dy) = self.direction.offset();
+        self.x += dx;
+        self.y += dy;
+    }
+}
+
+/// Dense finite board where cells never change on their own, only when they
+/// are set, e.g. by a [`Turmite`].
+#[derive(Debug, Clone)]
+pub struct StaticBoard {
+    bounds: Bounds,
+    cells: Vec<CellState>,
+    generation: u64,
+}
+
+impl StaticBoard {
+    pub fn new(bounds: Bounds) -> Self {
+        Self {
+            bounds,
+            cells: vec![CellState::Dead; bounds.width() * bounds.height()],
+            generation: 0,
+        }
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if self.bounds.contains(x, y) {
+            let col = (x - self.bounds.min_x) as usize;
+            let row = (y - self.bounds.min_y) as usize;
+            Some(row * self.bounds.width() + col)
+        } else {
+            None
+        }
+    }
+}
+
+impl Universe for StaticBoard {
+    fn step(&mut self) {
+        self.generation += 1;
+    }
+
+    fn jump(&mut self, exponent: u32) {
+        let generations = 1u64.checked_shl(exponent).unwrap_or(u64::MAX);
+        self.generation = self.generation.saturating_add(generations);
+    }
+
+    fn get(&self, x: i32, y: i32) -> CellState {
+        self.index(x, y)
+            .map(|i| self.cells[i])
+            .unwrap_or(CellState::Dead)
+    }
+
+    fn set(&mut self, x: i32, y: i32, state: CellState) {
+        if let Some(i) = self.index(x, y) {
+            self.cells[i] = state;
+        }
+    }
+
+    fn population(&self) -> usize {
+        self.cells.iter().filter(|c| **c != CellState::Dead).count()
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Some(self.bounds)
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+}
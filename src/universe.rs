@@ -0,0 +1,134 @@
+/// State of a single cell.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CellState {
+    /// Alive cell of the given color, always `0` unless the rule has more
+    /// than one color like Immigration.
+    Alive(u8),
+    #[default]
+    Dead,
+    /// Cell of a Generations rule that stopped being alive the given number
+    /// of generations ago (starting at 1).
+    Dying(u8),
+    /// Wire of a Wireworld circuit, see [`crate::WireworldBoard`].
+    Conductor,
+    /// Front of an electron moving along a wire.
+    ElectronHead,
+    /// Back of an electron, which turns back into a conductor.
+    ElectronTail,
+}
+
+impl CellState {
+    /// Whether the cell is alive, whatever its color.
+    pub fn is_alive(self) -> bool {
+        matches!(self, CellState::Alive(_))
+    }
+}
+
+impl From<u8> for CellState {
+    /// Converts from the state numbering used by Golly: `0` is dead, `1` is
+    /// alive and everything above is dying.
+    fn from(state: u8) -> Self {
+        match state {
+            0 => CellState::Dead,
+            1 => CellState::Alive(0),
+            n => CellState::Dying(n - 1),
+        }
+    }
+}
+
+/// Inclusive rectangle of cell coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub min_x: i32,
+    pub max_x: i32,
+    pub min_y: i32,
+    pub max_y: i32,
+}
+
+impl Bounds {
+    pub fn new(min_x: i32, max_x: i32, min_y: i32, max_y: i32) -> Self {
+        assert!(min_x <= max_x && min_y <= max_y, "empty bounds");
+        Self {
+            min_x,
+            max_x,
+            min_y,
+            max_y,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max_x - self.min_x + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max_y - self.min_y + 1) as usize
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.min_x && x <= self.max_x && y >= self.min_y && y <= self.max_y
+    }
+}
+
+/// A simulation that can be stepped one generation at a time.
+///
+/// Coordinates outside of [`Universe::bounds`] (if there are any) are always
+/// dead and setting them is ignored.
+pub trait Universe: Send + Sync {
+    /// Advance the simulation by one generation.
+    fn step(&mut self);
+
+    /// Advance the simulation by `2^exponent` generations.
+    ///
+    /// By default this steps through every one of them, so each increment
+    /// of `exponent` doubles the time it takes. Exponents above 63 are
+    /// clamped to 63, which already takes forever.
+    fn jump(&mut self, exponent: u32) {
+        debug_assert!(exponent < 64, "can't jump 2^{} generations", exponent);
+        for _ in 0..1u64 << exponent.min(63) {
+            self.step();
+        }
+    }
+
+    /// Reverses the direction the simulation runs in, so the following steps
+    /// undo the previous ones. Returns `false` if the universe can't run
+    /// backwards, most rules lose the information needed for it.
+    fn reverse(&mut self) -> bool {
+        false
+    }
+
+    fn get(&self, x: i32, y: i32) -> CellState;
+
+    fn set(&mut self, x: i32, y: i32, state: CellState);
+
+    /// Level of a cell between 0 and 1, for universes whose cells are more
+    /// than alive or dead. Cells of all other universes are 1 unless dead.
+    fn level(&self, x: i32, y: i32) -> f32 {
+        match self.get(x, y) {
+            CellState::Dead => 0.0,
+            _ => 1.0,
+        }
+    }
+
+    /// Number of cells that are currently not dead.
+    fn population(&self) -> usize;
+
+    /// Area containing all cells, `None` if the universe is unbounded.
+    fn bounds(&self) -> Option<Bounds>;
+
+    /// All cells in `area` that are not dead.
+    fn live_cells(&self, area: &Bounds) -> Vec<(i32, i32, CellState)> {
+        let mut cells = Vec::new();
+        for y in area.min_y..=area.max_y {
+            for x in area.min_x..=area.max_x {
+                let state = self.get(x, y);
+                if state != CellState::Dead {
+                    cells.push((x, y, state));
+                }
+            }
+        }
+        cells
+    }
+
+    /// Number of generations stepped so far, in either direction.
+    fn generation(&self) -> u64;
+}
@@ -0,0 +1,105 @@
+use std::mem;
+
+use crate::{Bounds, CellState, Neighborhood, Topology, Universe};
+
+/// Dense finite board running Wireworld, which simulates electrons moving
+/// along wires.
+///
+/// Dead cells are empty and never change. An electron head becomes a tail,
+/// a tail becomes a conductor again and a conductor becomes a head if one or
+/// two of its Moore neighbors are heads. Alive and dying cells of other
+/// families are left alone.
+#[derive(Debug, Clone)]
+pub struct WireworldBoard {
+    bounds: Bounds,
+    topology: Topology,
+    cells: Vec<CellState>,
+    next: Vec<CellState>,
+    generation: u64,
+}
+
+impl WireworldBoard {
+    pub fn new(bounds: Bounds) -> Self {
+        let len = bounds.width() * bounds.height();
+        Self {
+            bounds,
+            topology: Topology::default(),
+            cells: vec![CellState::Dead; len],
+            next: vec![CellState::Dead; len],
+            generation: 0,
+        }
+    }
+
+    pub fn with_topology(self, topology: Topology) -> Self {
+        Self { topology, ..self }
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if self.bounds.contains(x, y) {
+            let col = (x - self.bounds.min_x) as usize;
+            let row = (y - self.bounds.min_y) as usize;
+            Some(row * self.bounds.width() + col)
+        } else {
+            None
+        }
+    }
+
+    fn num_electron_heads(&self, x: i32, y: i32) -> usize {
+        Neighborhood::Moore
+            .offsets(x, y)
+            .filter_map(|(dx, dy)| self.topology.resolve(&self.bounds, x + dx, y + dy))
+            .filter(|&(nx, ny)| self.get(nx, ny) == CellState::ElectronHead)
+            .count()
+    }
+}
+
+impl Universe for WireworldBoard {
+    fn step(&mut self) {
+        let mut next = mem::take(&mut self.next);
+        let mut i = 0;
+        for y in self.bounds.min_y..=self.bounds.max_y {
+            for x in self.bounds.min_x..=self.bounds.max_x {
+                next[i] = match self.cells[i] {
+                    CellState::ElectronHead => CellState::ElectronTail,
+                    CellState::ElectronTail => CellState::Conductor,
+                    CellState::Conductor => match self.num_electron_heads(x, y) {
+                        1 | 2 => CellState::ElectronHead,
+                        _ => CellState::Conductor,
+                    },
+                    state => state,
+                };
+                i += 1;
+            }
+        }
+        self.next = mem::replace(&mut self.cells, next);
+        self.generation += 1;
+    }
+
+    fn get(&self, x: i32, y: i32) -> CellState {
+        self.index(x, y)
+            .map(|i| self.cells[i])
+            .unwrap_or(CellState::Dead)
+    }
+
+    fn set(&mut self, x: i32, y: i32, state: CellState) {
+        if let Some(i) = self.index(x, y) {
+            self.cells[i] = state;
+        }
+    }
+
+    fn population(&self) -> usize {
+        self.cells.iter().filter(|c| **c != CellState::Dead).count()
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Some(self.bounds)
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+}
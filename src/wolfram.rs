@@ -0,0 +1,294 @@
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Bounds, CellState, RuleError, Topology, Universe};
+
+/// Largest range of a totalistic [`Wolfram`] rule.
+pub const MAX_WOLFRAM_RANGE: u8 = 3;
+
+/// One-dimensional rule where the next color of a cell depends on the cells
+/// up to `range` cells to its left and right.
+///
+/// Elementary rules are written `W<code>` like in Golly, e.g. `W30` or
+/// `W110`, where bit `4l + 2c + r` of the code is the next state of a cell
+/// `c` between `l` and `r`.
+///
+/// Totalistic rules with `k` colors are written `T<code>/K<k>` with an
+/// optional `/R<range>` (default 1), e.g. `T1599/K3`. The next color is
+/// digit `s` of the code in base `k`, where `s` is the sum of the colors of
+/// the cell and its neighbors.
+///
+/// Colors are the state numbers used by Golly, see [`CellState::from`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Wolfram {
+    code: u64,
+    colors: u8,
+    range: u8,
+    totalistic: bool,
+}
+
+impl Wolfram {
+    /// Elementary rule with the given Wolfram code.
+    pub fn elementary(code: u8) -> Self {
+        Self {
+            code: code.into(),
+            colors: 2,
+            range: 1,
+            totalistic: false,
+        }
+    }
+
+    /// Totalistic rule with the given number of colors and range.
+    ///
+    /// Fails if the code has more digits than there are sums.
+    pub fn totalistic(code: u64, colors: u8, range: u8) -> Result<Self, RuleError> {
+        if colors < 2 {
+            return Err(RuleError::StatesOutOfRange(colors.into()));
+        }
+        if range == 0 || range > MAX_WOLFRAM_RANGE {
+            return Err(RuleError::RangeOutOfRange(range.into()));
+        }
+        let rule = Self {
+            code,
+            colors,
+            range,
+            totalistic: true,
+        };
+        let sums = rule.cells() as u32 * (colors as u32 - 1) + 1;
+        if let Some(max) = (colors as u64).checked_pow(sums) {
+            if code >= max {
+                return Err(RuleError::Malformed(rule.to_string()));
+            }
+        }
+        Ok(rule)
+    }
+
+    pub fn code(&self) -> u64 {
+        self.code
+    }
+
+    /// Number of colors including 0 (dead).
+    pub fn colors(&self) -> u8 {
+        self.colors
+    }
+
+    pub fn range(&self) -> u8 {
+        self.range
+    }
+
+    pub fn is_totalistic(&self) -> bool {
+        self.totalistic
+    }
+
+    /// Number of cells the next color depends on, including the cell itself.
+    fn cells(&self) -> usize {
+        2 * self.range as usize + 1
+    }
+
+    /// Color of a cell in the next generation, given the colors of the cells
+    /// from `range` cells to its left to `range` cells to its right.
+    pub fn next_color(&self, colors: &[u8]) -> u8 {
+        if !self.totalistic {
+            let index = colors
+                .iter()
+                .fold(0, |index, &c| index << 1 | (c != 0) as u64);
+            return (self.code >> index & 1) as u8;
+        }
+        let sum: u32 = colors.iter().map(|&c| c as u32).sum();
+        match (self.colors as u64).checked_pow(sum) {
+            Some(place) => (self.code / place % self.colors as u64) as u8,
+            None => 0,
+        }
+    }
+}
+
+fn saturate(n: u64) -> u32 {
+    u32::try_from(n).unwrap_or(u32::MAX)
+}
+
+impl FromStr for Wolfram {
+    type Err = RuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let malformed = || RuleError::Malformed(s.to_string());
+        let number = |part: &str| -> Result<u64, RuleError> {
+            if let Some(c) = part.chars().find(|c| !c.is_ascii_digit()) {
+                return Err(RuleError::UnexpectedChar(c));
+            }
+            part.parse().map_err(|_| malformed())
+        };
+
+        let mut parts = s.trim().split('/');
+        let first = parts.next().unwrap_or("");
+        let mut chars = first.chars();
+        match chars.next().map(|c| c.to_ascii_uppercase()) {
+            Some('W') => {
+                if parts.next().is_some() {
+                    return Err(malformed());
+                }
+                let code = number(chars.as_str())?;
+                u8::try_from(code)
+                    .map(Self::elementary)
+                    .map_err(|_| malformed())
+            }
+            Some('T') => {
+                let code = number(chars.as_str())?;
+                let mut colors = None;
+                let mut range = None;
+                for part in parts {
+                    let mut chars = part.chars();
+                    let letter = chars.next().map(|c| c.to_ascii_uppercase());
+                    let (target, letter) = match letter {
+                        Some('K') => (&mut colors, 'K'),
+                        Some('R') => (&mut range, 'R'),
+                        Some(c) => return Err(RuleError::UnexpectedChar(c)),
+                        None => return Err(malformed()),
+                    };
+                    if target.replace(number(chars.as_str())?).is_some() {
+                        return Err(RuleError::Duplicate(letter));
+                    }
+                }
+                let colors = colors.ok_or_else(malformed)?;
+                let colors = u8::try_from(colors)
+                    .map_err(|_| RuleError::StatesOutOfRange(saturate(colors)))?;
+                let range = range.unwrap_or(1);
+                let range =
+                    u8::try_from(range).map_err(|_| RuleError::RangeOutOfRange(saturate(range)))?;
+                Self::totalistic(code, colors, range)
+            }
+            _ => Err(malformed()),
+        }
+    }
+}
+
+impl fmt::Display for Wolfram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.totalistic {
+            return write!(f, "W{}", self.code);
+        }
+        write!(f, "T{}/K{}", self.code, self.colors)?;
+        if self.range != 1 {
+            write!(f, "/R{}", self.range)?;
+        }
+        Ok(())
+    }
+}
+
+/// Space-time diagram of a one-dimensional [`Wolfram`] rule.
+///
+/// Every row of the bounds is a generation of the cells between `min_x` and
+/// `max_x`. The first generation is the top row at `max_y`, each step adds
+/// the next one below. Once the bottom row is reached the diagram scrolls up
+/// so the newest generation is always at `min_y`.
+///
+/// Setting a cell changes the generation shown in its row, only changes to
+/// the newest one affect later generations. The left and right edges are
+/// joined if the [`Topology`] joins them.
+#[derive(Debug, Clone)]
+pub struct WolframBoard {
+    bounds: Bounds,
+    rule: Wolfram,
+    topology: Topology,
+    // generations from the one shown at `max_y` to the newest
+    history: VecDeque<Vec<u8>>,
+    generation: u64,
+}
+
+impl WolframBoard {
+    pub fn new(bounds: Bounds, rule: Wolfram) -> Self {
+        let mut history = VecDeque::with_capacity(bounds.height());
+        history.push_back(vec![0; bounds.width()]);
+        Self {
+            bounds,
+            rule,
+            topology: Topology::default(),
+            history,
+            generation: 0,
+        }
+    }
+
+    pub fn with_topology(self, topology: Topology) -> Self {
+        Self { topology, ..self }
+    }
+
+    pub fn rule(&self) -> Wolfram {
+        self.rule
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Row and column of a cell in the history.
+    fn index(&self, x: i32, y: i32) -> Option<(usize, usize)> {
+        if self.bounds.contains(x, y) {
+            let row = (self.bounds.max_y - y) as usize;
+            let col = (x - self.bounds.min_x) as usize;
+            Some((row, col)).filter(|&(row, _)| row < self.history.len())
+        } else {
+            None
+        }
+    }
+}
+
+impl Universe for WolframBoard {
+    fn step(&mut self) {
+        let line = Bounds::new(self.bounds.min_x, self.bounds.max_x, 0, 0);
+        let current = self.history.back().expect("history is never empty");
+        let range = self.rule.range as i32;
+        let mut colors = Vec::with_capacity(self.rule.cells());
+        let next = (self.bounds.min_x..=self.bounds.max_x)
+            .map(|x| {
+                colors.clear();
+                colors.extend((x - range..=x + range).map(|x| {
+                    match self.topology.resolve(&line, x, 0) {
+                        Some((x, _)) => current[(x - self.bounds.min_x) as usize],
+                        None => 0,
+                    }
+                }));
+                self.rule.next_color(&colors)
+            })
+            .collect();
+
+        self.history.push_back(next);
+        if self.history.len() > self.bounds.height() {
+            self.history.pop_front();
+        }
+        self.generation += 1;
+    }
+
+    fn get(&self, x: i32, y: i32) -> CellState {
+        self.index(x, y)
+            .map(|(row, col)| CellState::from(self.history[row][col]))
+            .unwrap_or(CellState::Dead)
+    }
+
+    fn set(&mut self, x: i32, y: i32, state: CellState) {
+        let color = match state {
+            CellState::Dead => 0,
+            CellState::Alive(_) => 1,
+            CellState::Dying(age) => age.saturating_add(1),
+            _ => 1,
+        };
+        if let Some((row, col)) = self.index(x, y) {
+            self.history[row][col] = color.min(self.rule.colors - 1);
+        }
+    }
+
+    fn population(&self) -> usize {
+        self.history
+            .back()
+            .map(|line| line.iter().filter(|&&c| c != 0).count())
+            .unwrap_or(0)
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Some(self.bounds)
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+}
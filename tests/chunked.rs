@@ -0,0 +1,61 @@
+//! `ChunkedUniverse` must run exactly like a `Board` that is large enough to
+//! never reach its edges, also where patterns cross from one chunk into
+//! another and at negative coordinates.
+
+mod common;
+
+use game_of_live::{Board, Bounds, ChunkedUniverse, Rule, Universe, CHUNK_SIZE};
+
+use common::{alive_cells, assert_same_cells, place, soup};
+
+// patterns grow by at most one cell per generation, so the soup can't reach
+// the edges in the generations that are compared
+const AREA: Bounds = Bounds {
+    min_x: -96,
+    max_x: 96,
+    min_y: -96,
+    max_y: 96,
+};
+
+fn start(rule: Rule, cells: &[(i32, i32)]) -> (Board, ChunkedUniverse) {
+    let mut board = Board::new(AREA, rule);
+    let mut chunked = ChunkedUniverse::new(rule).unwrap();
+    place(&mut board, cells);
+    place(&mut chunked, cells);
+    (board, chunked)
+}
+
+#[test]
+fn soups_follow_board() {
+    // around the origin, where four chunks meet
+    let area = Bounds::new(-12, 11, -12, 11);
+    for (seed, rule) in ["B3/S23", "B36/S23", "B3678/S34678", "B2/S"]
+        .iter()
+        .enumerate()
+    {
+        let (mut board, mut chunked) = start(rule.parse().unwrap(), &soup(seed as u32, &area));
+        assert_same_cells(rule, &mut chunked, &mut board, &AREA, 40);
+        assert_eq!(chunked.population(), board.population());
+    }
+}
+
+#[test]
+fn glider_crosses_chunks() {
+    // moving towards negative x and y, one cell diagonally every 4
+    // generations
+    let glider = [(1, 2), (0, 1), (2, 0), (1, 0), (0, 0)];
+    let glider: Vec<_> = glider.iter().map(|&(x, y)| (x - 2, y - 2)).collect();
+    let (_, mut chunked) = start(Rule::conway(), &glider);
+    let distance = 2 * CHUNK_SIZE + 5;
+    for _ in 0..4 * distance {
+        chunked.step();
+    }
+
+    let area = Bounds::new(-200, 200, -200, 200);
+    let moved = glider
+        .iter()
+        .map(|&(x, y)| (x - distance, y - distance))
+        .collect();
+    assert_eq!(alive_cells(&chunked, &area), moved);
+    assert_eq!(chunked.population(), 5);
+}
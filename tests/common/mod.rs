@@ -0,0 +1,65 @@
+//! Helpers shared by the integration tests.
+
+// every test uses only some of them
+#![allow(dead_code)]
+
+use std::collections::BTreeSet;
+
+use game_of_live::{Bounds, CellState, Universe};
+
+/// Random soup filling `area` with about one alive cell in four, the same
+/// seed always gives the same soup.
+pub fn soup(seed: u32, area: &Bounds) -> Vec<(i32, i32)> {
+    let mut seed = seed;
+    let mut cells = Vec::new();
+    for y in area.min_y..=area.max_y {
+        for x in area.min_x..=area.max_x {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            if seed >> 30 == 0 {
+                cells.push((x, y));
+            }
+        }
+    }
+    cells
+}
+
+/// Sets `cells` alive.
+pub fn place(universe: &mut dyn Universe, cells: &[(i32, i32)]) {
+    for &(x, y) in cells {
+        universe.set(x, y, CellState::Alive(0));
+    }
+}
+
+/// Cells in `area` that are alive, whatever their color.
+pub fn alive_cells(universe: &dyn Universe, area: &Bounds) -> BTreeSet<(i32, i32)> {
+    universe
+        .live_cells(area)
+        .into_iter()
+        .filter(|(_, _, state)| state.is_alive())
+        .map(|(x, y, _)| (x, y))
+        .collect()
+}
+
+/// Steps `universe` next to `expected` and checks that the same cells in
+/// `area` are alive in each of the `generations` generations, `name` tells
+/// the runs apart in the message.
+pub fn assert_same_cells(
+    name: &str,
+    universe: &mut dyn Universe,
+    expected: &mut dyn Universe,
+    area: &Bounds,
+    generations: u32,
+) {
+    for generation in 0..generations {
+        assert_eq!(
+            alive_cells(universe, area),
+            alive_cells(expected, area),
+            "{} differs in generation {}",
+            name,
+            generation
+        );
+        universe.step();
+        expected.step();
+    }
+    assert_eq!(universe.generation(), expected.generation());
+}
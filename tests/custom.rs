@@ -0,0 +1,101 @@
+//! Rules implemented as a `CellRule` must run like the built-in rules they
+//! imitate and be found by their name in any case.
+
+mod common;
+
+use game_of_live::{
+    Automaton, Board, Bounds, CellRule, CellState, ConwayLife, CustomBoard, CustomRule, Rule,
+    RuleError, RuleRegistry, Topology, Universe,
+};
+
+use common::{assert_same_cells, place, soup};
+
+/// Brian's Brain, the same as the `B2/S/C3` rule.
+struct BriansBrain;
+
+impl CellRule for BriansBrain {
+    fn name(&self) -> &str {
+        "BriansBrain"
+    }
+
+    fn states(&self) -> u8 {
+        3
+    }
+
+    fn next_state(&self, state: u8, neighbors: &[u8]) -> u8 {
+        let alive = neighbors.iter().filter(|&&neighbor| neighbor == 1).count();
+        match (state, alive) {
+            (0, 2) => 1,
+            (0, _) => 0,
+            (1, _) => 2,
+            _ => 0,
+        }
+    }
+}
+
+#[test]
+fn conway_life_follows_board() {
+    let bounds = Bounds::new(-20, 19, -15, 14);
+    for (seed, &topology) in [
+        Topology::Bounded,
+        Topology::Torus,
+        Topology::ProjectivePlane,
+    ]
+    .iter()
+    .enumerate()
+    {
+        let cells = soup(seed as u32, &bounds);
+        let mut board = Board::new(bounds, Rule::conway()).with_topology(topology);
+        let mut custom =
+            CustomBoard::new(bounds, CustomRule::new(ConwayLife)).with_topology(topology);
+        place(&mut board, &cells);
+        place(&mut custom, &cells);
+        let name = format!("{:?}", topology);
+        assert_same_cells(&name, &mut custom, &mut board, &bounds, 24);
+    }
+}
+
+#[test]
+fn dying_states_follow_generations() {
+    let bounds = Bounds::new(-12, 11, -12, 11);
+    let cells = soup(9, &bounds);
+    let mut board = Board::new(bounds, "B2/S/C3".parse().unwrap());
+    let mut custom = CustomBoard::new(bounds, CustomRule::new(BriansBrain));
+    place(&mut board, &cells);
+    place(&mut custom, &cells);
+    for _ in 0..12 {
+        board.step();
+        custom.step();
+        assert_eq!(custom.live_cells(&bounds), board.live_cells(&bounds));
+    }
+    assert!(custom
+        .live_cells(&bounds)
+        .iter()
+        .any(|&(_, _, state)| state == CellState::Dying(1)));
+}
+
+#[test]
+fn rules_are_found_by_name() {
+    let mut registry = RuleRegistry::default();
+    let brain = registry.register(BriansBrain).unwrap();
+    assert_eq!(registry.get("briansbrain"), Some(brain.clone()));
+    assert_eq!(registry.get(" CONWAYLIFE ").unwrap().name(), "ConwayLife");
+    assert_eq!(registry.get("Seeds"), None);
+    assert_eq!(
+        registry.names().collect::<Vec<_>>(),
+        ["BriansBrain", "ConwayLife"]
+    );
+    assert_eq!(
+        registry.register(BriansBrain).unwrap_err(),
+        RuleError::DuplicateRule("BriansBrain".to_string())
+    );
+
+    // custom rules are written as their name, so they can't be the default
+    assert_eq!(Automaton::Custom(brain).to_string(), "BriansBrain");
+    assert_eq!(CustomRule::new(ConwayLife).to_string(), "ConwayLife");
+    assert_eq!(Automaton::default(), Automaton::Life(Rule::conway()));
+    assert_eq!(
+        Automaton::default().to_string().parse(),
+        Ok(Automaton::default())
+    );
+}
@@ -0,0 +1,66 @@
+//! Cyclic cells must advance to the next state when enough of their
+//! neighbors are in it, rock-paper-scissors cells to the state beating them.
+
+use game_of_live::{Automaton, Bounds, CellState, Cyclic, CyclicBoard, RuleError, Universe};
+
+/// Cells of `board` in `state`.
+fn in_state(board: &CyclicBoard, state: u8) -> usize {
+    let bounds = board.bounds().unwrap();
+    board
+        .live_cells(&bounds)
+        .into_iter()
+        .filter(|&(_, _, cell)| cell == CellState::Alive(state))
+        .count()
+}
+
+#[test]
+fn states_spread_to_the_previous_state() {
+    let rule: Cyclic = "R1/T1/C3".parse().unwrap();
+    let mut board = CyclicBoard::new(Bounds::new(-5, 5, -5, 5), rule);
+    board.set(0, 0, CellState::Alive(1));
+    board.step();
+    assert_eq!(in_state(&board, 1), 9);
+    board.step();
+    assert_eq!(in_state(&board, 1), 25);
+    assert_eq!(board.get(2, -2), CellState::Alive(1));
+
+    // state 2 takes over state 1, but not state 0
+    board.set(0, 0, CellState::Alive(2));
+    board.step();
+    assert_eq!(in_state(&board, 2), 9);
+    assert_eq!(in_state(&board, 1), 49 - 9);
+    assert_eq!(board.population(), 121);
+}
+
+#[test]
+fn rock_paper_scissors_with_three_states_is_cyclic() {
+    let bounds = Bounds::new(0, 39, 0, 29);
+    let cyclic = CyclicBoard::new(bounds, "R1/T3/C3".parse().unwrap());
+    let rps = CyclicBoard::new(bounds, "R1/T3/C3/RPS".parse().unwrap());
+    let (mut cyclic, mut rps) = (cyclic.with_random_states(4), rps.with_random_states(4));
+    for _ in 0..20 {
+        cyclic.step();
+        rps.step();
+    }
+    assert_eq!(cyclic.live_cells(&bounds), rps.live_cells(&bounds));
+}
+
+#[test]
+fn rules_are_written_as_parsed() {
+    for rule in &["R1/T3/C3/NM", "R2/T5/C8/NN", "R1/T2/C5/NM/RPS"] {
+        let parsed: Cyclic = rule.parse().unwrap();
+        assert_eq!(&parsed.to_string(), rule);
+        assert_eq!(rule.parse::<Automaton>(), Ok(Automaton::Cyclic(parsed)));
+    }
+    assert_eq!(
+        "R1/T3/C3".parse::<Cyclic>().unwrap().to_string(),
+        "R1/T3/C3/NM"
+    );
+
+    let error = |rule: &str| rule.parse::<Cyclic>().unwrap_err();
+    assert!(matches!(error("R1/T0/C3"), RuleError::Unsupported(_)));
+    assert_eq!(error("R1/T9/C3"), RuleError::CountOutOfRange(9, 8));
+    assert_eq!(error("R1/T1/C1"), RuleError::StatesOutOfRange(1));
+    assert!(matches!(error("R1/T1/C2/RPS"), RuleError::Unsupported(_)));
+    assert_eq!(error("R1/T1/C3/C4"), RuleError::Duplicate('C'));
+}
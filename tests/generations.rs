@@ -0,0 +1,76 @@
+//! Generations rules let cells that don't survive pass through their dying
+//! states before they are dead, and dying cells don't count as neighbors.
+
+mod common;
+
+use game_of_live::{Board, Bounds, CellState, Rule, RuleError, Universe};
+
+use common::place;
+
+fn start(rule: &str, cells: &[(i32, i32)]) -> Board {
+    let mut board = Board::new(Bounds::new(-5, 5, -5, 5), rule.parse().unwrap());
+    place(&mut board, cells);
+    board
+}
+
+#[test]
+fn lone_cells_decay() {
+    let mut board = start("B2/S/C3", &[(0, 0)]);
+    board.step();
+    assert_eq!(board.get(0, 0), CellState::Dying(1));
+    board.step();
+    assert_eq!(board.get(0, 0), CellState::Dead);
+
+    let mut board = start("B2/S345/C4", &[(0, 0)]);
+    let mut states = Vec::new();
+    for _ in 0..3 {
+        board.step();
+        states.push(board.get(0, 0));
+    }
+    assert_eq!(
+        states,
+        [CellState::Dying(1), CellState::Dying(2), CellState::Dead]
+    );
+    assert_eq!(board.population(), 0);
+}
+
+#[test]
+fn brians_brain_domino_moves() {
+    // the dying domino blocks births behind the new one
+    let mut board = start("B2/S/C3", &[(0, 0), (1, 0)]);
+    board.step();
+    for &(x, y) in &[(0, -1), (1, -1), (0, 1), (1, 1)] {
+        assert_eq!(board.get(x, y), CellState::Alive(0), "({}, {})", x, y);
+    }
+    assert_eq!(board.get(0, 0), CellState::Dying(1));
+    assert_eq!(board.get(1, 0), CellState::Dying(1));
+    assert_eq!(board.population(), 6);
+
+    board.step();
+    for &(x, y) in &[(0, -2), (1, -2), (0, 2), (1, 2)] {
+        assert_eq!(board.get(x, y), CellState::Alive(0), "({}, {})", x, y);
+    }
+    assert_eq!(board.get(0, 0), CellState::Dead);
+    assert_eq!(board.get(0, 1), CellState::Dying(1));
+}
+
+#[test]
+fn rules_are_written_as_parsed() {
+    for rule in &["B2/S/C3", "B2/S345/C4", "B3/S23/C255"] {
+        let parsed: Rule = rule.parse().unwrap();
+        assert_eq!(&parsed.to_string(), rule);
+    }
+    // two states are a plain Life-like rule
+    assert_eq!("B3/S23/C2".parse::<Rule>(), Ok(Rule::conway()));
+    assert_eq!(Rule::conway().to_string(), "B3/S23");
+
+    assert_eq!(
+        "B2/S/C1".parse::<Rule>(),
+        Err(RuleError::StatesOutOfRange(1))
+    );
+    assert_eq!(
+        "B2/S/C256".parse::<Rule>(),
+        Err(RuleError::StatesOutOfRange(256))
+    );
+    assert_eq!("B2/S/C3/C4".parse::<Rule>(), Err(RuleError::Duplicate('C')));
+}
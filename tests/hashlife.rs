@@ -0,0 +1,90 @@
+//! HashLife must run exactly like a `Board` that is large enough to never
+//! reach its edges, both one step at a time and in jumps.
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use game_of_live::{Board, Bounds, HashLife, Rule, Universe};
+
+use common::{alive_cells, assert_same_cells, place, soup};
+
+// patterns grow by at most one cell per generation, so soups in the middle
+// can't reach the edges in the generations that are compared
+const AREA: Bounds = Bounds {
+    min_x: -72,
+    max_x: 72,
+    min_y: -72,
+    max_y: 72,
+};
+
+const SOUP: Bounds = Bounds {
+    min_x: -8,
+    max_x: 7,
+    min_y: -8,
+    max_y: 7,
+};
+
+fn start(rule: Rule, cells: &[(i32, i32)]) -> (Board, HashLife) {
+    let mut board = Board::new(AREA, rule);
+    let mut hashlife = HashLife::new(rule).unwrap();
+    place(&mut board, cells);
+    place(&mut hashlife, cells);
+    (board, hashlife)
+}
+
+#[test]
+fn soups_follow_board() {
+    for (seed, rule) in ["B3/S23", "B36/S23", "B3678/S34678", "B2/S"]
+        .iter()
+        .enumerate()
+    {
+        let (mut board, mut hashlife) = start(rule.parse().unwrap(), &soup(seed as u32, &SOUP));
+        assert_same_cells(rule, &mut hashlife, &mut board, &AREA, 48);
+    }
+}
+
+#[test]
+fn jumps_follow_board() {
+    for (seed, rule) in ["B3/S23", "B36/S23", "B3678/S34678"].iter().enumerate() {
+        let (mut board, mut hashlife) = start(rule.parse().unwrap(), &soup(seed as u32, &SOUP));
+        // 63 generations in jumps of growing size
+        for exponent in 0..6 {
+            for _ in 0..1 << exponent {
+                board.step();
+            }
+            hashlife.jump(exponent);
+            assert_eq!(
+                alive_cells(&hashlife, &AREA),
+                alive_cells(&board, &AREA),
+                "{} differs after jumping 2^{}",
+                rule,
+                exponent
+            );
+            assert_eq!(hashlife.generation(), board.generation());
+        }
+    }
+}
+
+#[test]
+fn r_pentomino_stabilizes() {
+    let (_, mut hashlife) = start(Rule::conway(), &[(1, 0), (2, 0), (0, 1), (1, 1), (1, 2)]);
+    // 1103 generations
+    for &exponent in &[10, 6, 3, 2, 1, 0] {
+        hashlife.jump(exponent);
+    }
+    assert_eq!(hashlife.generation(), 1103);
+    assert_eq!(hashlife.population(), 116);
+}
+
+#[test]
+fn glider_moves_far() {
+    let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+    let (_, mut hashlife) = start(Rule::conway(), &glider);
+    // one cell diagonally every 4 generations
+    hashlife.jump(12);
+    let moved: BTreeSet<_> = glider.iter().map(|&(x, y)| (x + 1024, y + 1024)).collect();
+    let area = Bounds::new(1000, 1100, 1000, 1100);
+    assert_eq!(alive_cells(&hashlife, &area), moved);
+    assert_eq!(hashlife.population(), 5);
+}
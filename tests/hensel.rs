@@ -0,0 +1,129 @@
+//! The Hensel letters of every neighbor count must name each configuration
+//! of the Moore neighborhood exactly once, and every engine must run
+//! non-totalistic rules the same way.
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use game_of_live::{Board, Bounds, CellState, HashLife, Rule, SparseUniverse};
+
+use common::{assert_same_cells, place, soup};
+
+/// Letters of 0 to 8 alive neighbors as in Golly.
+const LETTERS: [&str; 9] = [
+    "",
+    "ce",
+    "aceikn",
+    "aceijknqry",
+    "aceijknqrtwyz",
+    "aceijknqry",
+    "aceikn",
+    "ce",
+    "",
+];
+
+/// Configurations that cause a birth, bit `i` is the `i`-th Moore neighbor.
+fn births(rule: &Rule) -> BTreeSet<u8> {
+    (0..=255)
+        .filter(|&neighbors| rule.next_state_moore(CellState::Dead, neighbors).is_alive())
+        .collect()
+}
+
+/// Rotates the configuration by 90 degrees.
+fn rotate(neighbors: u8) -> u8 {
+    // offsets row by row without the center, and where each one moves to
+    const ROTATED: [u8; 8] = [2, 4, 7, 1, 6, 0, 3, 5];
+    (0..8)
+        .filter(|bit| neighbors & (1 << bit) != 0)
+        .fold(0, |rotated, bit| rotated | 1 << ROTATED[bit])
+}
+
+/// Mirrors the configuration horizontally.
+fn mirror(neighbors: u8) -> u8 {
+    const MIRRORED: [u8; 8] = [2, 1, 0, 4, 3, 7, 6, 5];
+    (0..8)
+        .filter(|bit| neighbors & (1 << bit) != 0)
+        .fold(0, |mirrored, bit| mirrored | 1 << MIRRORED[bit])
+}
+
+#[test]
+fn letters_split_every_count() {
+    for (count, letters) in LETTERS.iter().enumerate() {
+        let all: BTreeSet<u8> = (0..=255)
+            .filter(|c: &u8| c.count_ones() as usize == count)
+            .collect();
+        let mut named = BTreeSet::new();
+        for letter in letters.chars() {
+            let rule: Rule = format!("B{}{}/S", count, letter).parse().unwrap();
+            let births = births(&rule);
+            assert!(!births.is_empty(), "{}{} is empty", count, letter);
+            assert!(
+                births.is_subset(&all),
+                "{}{} has other counts",
+                count,
+                letter
+            );
+            for &c in &births {
+                // isotropic, so every letter is closed under the symmetries
+                assert!(births.contains(&rotate(c)), "{}{}", count, letter);
+                assert!(births.contains(&mirror(c)), "{}{}", count, letter);
+                assert!(named.insert(c), "{}{} overlaps {:08b}", count, letter, c);
+            }
+        }
+        if !letters.is_empty() {
+            assert_eq!(named, all, "letters of {} leave gaps", count);
+        }
+    }
+}
+
+#[test]
+fn single_neighbors_are_corners_and_edges() {
+    let corner: Rule = "B1c/S".parse().unwrap();
+    let edge: Rule = "B1e/S".parse().unwrap();
+    // (-1, -1), (1, -1), (-1, 1) and (1, 1)
+    assert_eq!(births(&corner), [1, 4, 32, 128].iter().copied().collect());
+    assert_eq!(births(&edge), [2, 8, 16, 64].iter().copied().collect());
+}
+
+#[test]
+fn rules_are_written_as_parsed() {
+    for rule in &[
+        "B3/S2-i34q",
+        "B2-a/S12",
+        "B2ce3aiy/S1c2ea",
+        "B2i34cz/S2-in3",
+        "B3-cnqy/S23-a4ceny",
+        "B2e3-c/S23/C3",
+    ] {
+        let parsed: Rule = rule.parse().unwrap();
+        assert_eq!(&parsed.to_string(), rule);
+    }
+
+    // and every single letter survives a round trip
+    for (count, letters) in LETTERS.iter().enumerate() {
+        for letter in letters.chars() {
+            let rule: Rule = format!("B{0}{1}/S{0}{1}", count, letter).parse().unwrap();
+            let again: Rule = rule.to_string().parse().unwrap();
+            assert_eq!(again, rule, "{}", rule);
+        }
+    }
+}
+
+#[test]
+fn engines_agree_on_tlife() {
+    let rule: Rule = "B3/S2-i34q".parse().unwrap();
+    assert!(!rule.is_totalistic());
+    // the soup can't reach the edges of the board in time
+    let area = Bounds::new(-80, 80, -80, 80);
+    let cells = soup(7, &Bounds::new(-12, 11, -12, 11));
+    let mut board = Board::new(area, rule);
+    let mut sparse = SparseUniverse::new(rule).unwrap();
+    let mut hashlife = HashLife::new(rule).unwrap();
+    place(&mut board, &cells);
+    place(&mut sparse, &cells);
+    place(&mut hashlife, &cells);
+
+    assert_same_cells("Sparse", &mut sparse, &mut board.clone(), &area, 60);
+    assert_same_cells("HashLife", &mut hashlife, &mut board, &area, 60);
+}
@@ -0,0 +1,41 @@
+//! Hexagonal rules count the 6 neighbors of a cell, which depend on whether
+//! it is in an even or an odd row.
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use game_of_live::{Board, Bounds, Neighborhood, Rule, RuleError, Universe};
+
+use common::{alive_cells, place};
+
+fn births_around(x: i32, y: i32) -> BTreeSet<(i32, i32)> {
+    let area = Bounds::new(-5, 5, -5, 5);
+    let mut board = Board::new(area, "B1/SH".parse().unwrap());
+    place(&mut board, &[(x, y)]);
+    board.step();
+    alive_cells(&board, &area)
+}
+
+#[test]
+fn single_cells_give_birth_to_their_neighbors() {
+    // odd rows are shifted half a cell towards positive x
+    let even_row = [(-1, -1), (0, -1), (-1, 0), (1, 0), (-1, 1), (0, 1)];
+    assert_eq!(births_around(0, 0), even_row.iter().copied().collect());
+    let odd_row = [(0, 0), (1, 0), (-1, 1), (1, 1), (0, 2), (1, 2)];
+    assert_eq!(births_around(0, 1), odd_row.iter().copied().collect());
+}
+
+#[test]
+fn rules_are_written_as_parsed() {
+    for rule in &["B2/S34H", "B245/S3H", "B2/S/C3H"] {
+        let parsed: Rule = rule.parse().unwrap();
+        assert_eq!(&parsed.to_string(), rule);
+        assert_eq!(parsed.neighborhood(), Neighborhood::Hexagonal);
+    }
+    assert_eq!("B2/S34h".parse::<Rule>(), "B2/S34H".parse::<Rule>());
+    assert_eq!(
+        "B7/SH".parse::<Rule>(),
+        Err(RuleError::CountOutOfRange(7, 6))
+    );
+}
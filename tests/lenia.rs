@@ -0,0 +1,84 @@
+//! Lenia cells must grow or shrink by the growth function of the weighted
+//! levels around them.
+
+use game_of_live::{Automaton, Bounds, Lenia, LeniaBoard, Topology, Universe};
+
+#[test]
+fn uniform_levels_grow_evenly() {
+    // the kernel adds up to 1, so every cell sees the level of the board
+    let rule = Lenia {
+        radius: 5,
+        time: 10.0,
+        mu: 0.5,
+        sigma: 0.1,
+    };
+    let bounds = Bounds::new(0, 19, 0, 19);
+    let mut board = LeniaBoard::new(bounds, rule)
+        .unwrap()
+        .with_topology(Topology::Torus);
+    for y in 0..20 {
+        for x in 0..20 {
+            board.set_level(x, y, 0.5);
+        }
+    }
+    assert_eq!(rule.growth(0.5), 1.0);
+    board.step();
+    for y in 0..20 {
+        for x in 0..20 {
+            assert!((board.level(x, y) - 0.6).abs() < 1e-4, "({}, {})", x, y);
+        }
+    }
+}
+
+#[test]
+fn single_cells_fade() {
+    let mut board = LeniaBoard::new(Bounds::new(-20, 20, -20, 20), Lenia::default()).unwrap();
+    board.set_level(0, 0, 1.0);
+    // nothing around it, so it shrinks by 1 / time every step
+    board.step();
+    assert!((board.level(0, 0) - 0.9).abs() < 1e-4);
+    assert_eq!(board.population(), 1);
+    for _ in 0..10 {
+        board.step();
+    }
+    assert_eq!(board.population(), 0);
+    assert_eq!(board.level(0, 0), 0.0);
+}
+
+#[test]
+fn invalid_parameters_fail() {
+    let invalid = [
+        Lenia {
+            radius: 0,
+            ..Lenia::default()
+        },
+        Lenia {
+            radius: 65,
+            ..Lenia::default()
+        },
+        Lenia {
+            sigma: 0.0,
+            ..Lenia::default()
+        },
+        Lenia {
+            time: -1.0,
+            ..Lenia::default()
+        },
+    ];
+    for &rule in &invalid {
+        assert!(
+            LeniaBoard::new(Bounds::new(0, 9, 0, 9), rule).is_err(),
+            "{:?}",
+            rule
+        );
+    }
+}
+
+#[test]
+fn rule_is_written_as_parsed() {
+    for rule in &["Lenia", "lenia"] {
+        assert_eq!(rule.parse(), Ok(Automaton::Lenia));
+    }
+    assert_eq!(Automaton::Lenia.to_string(), "Lenia");
+    assert!(Automaton::Lenia.is_continuous());
+}
@@ -0,0 +1,69 @@
+//! Larger than Life rules count every cell within their range, a range of 1
+//! without the middle cell is a Life-like rule.
+
+mod common;
+
+use game_of_live::{
+    Board, Bounds, LargerThanLife, LtlBoard, LtlNeighborhood, Rule, RuleError, Topology, Universe,
+};
+
+use common::{alive_cells, assert_same_cells, place, soup};
+
+#[test]
+fn range_one_follows_board() {
+    let bounds = Bounds::new(-20, 19, -15, 14);
+    let rule: LargerThanLife = "R1,C0,M0,S2..3,B3..3,NM".parse().unwrap();
+    for (seed, &topology) in [Topology::Bounded, Topology::Torus, Topology::KleinBottle]
+        .iter()
+        .enumerate()
+    {
+        let cells = soup(seed as u32, &bounds);
+        let mut board = Board::new(bounds, Rule::conway()).with_topology(topology);
+        let mut ltl = LtlBoard::new(bounds, rule).with_topology(topology);
+        place(&mut board, &cells);
+        place(&mut ltl, &cells);
+        let name = format!("{:?}", topology);
+        assert_same_cells(&name, &mut ltl, &mut board, &bounds, 24);
+    }
+}
+
+#[test]
+fn single_cells_fill_their_neighborhood() {
+    let area = Bounds::new(-10, 10, -10, 10);
+    let square: LargerThanLife = "R2,C0,M0,S0..0,B1..1,NM".parse().unwrap();
+    let diamond: LargerThanLife = "R2,C0,M0,S0..0,B1..1,NN".parse().unwrap();
+    for &rule in &[square, diamond] {
+        let mut board = LtlBoard::new(area, rule);
+        place(&mut board, &[(3, -2)]);
+        board.step();
+        let expected = (-2..=2)
+            .flat_map(|dy| (-2..=2).map(move |dx| (dx, dy)))
+            .filter(|&(dx, dy)| match rule.neighborhood() {
+                LtlNeighborhood::Moore => true,
+                LtlNeighborhood::VonNeumann => i32::abs(dx) + i32::abs(dy) <= 2,
+            })
+            .map(|(dx, dy)| (3 + dx, -2 + dy))
+            .collect();
+        // the cell itself survives with no alive neighbors
+        assert_eq!(alive_cells(&board, &area), expected, "{}", rule);
+        assert_eq!(board.population(), rule.size() + 1);
+    }
+}
+
+#[test]
+fn rules_are_written_as_parsed() {
+    for rule in &["R5,C0,M1,S34..58,B34..45,NM", "R2,C3,M0,S1..2,B3..4,NN"] {
+        let parsed: LargerThanLife = rule.parse().unwrap();
+        assert_eq!(&parsed.to_string(), rule);
+    }
+    // left out parts take their defaults
+    let short: LargerThanLife = "R1,S2..3,B3".parse().unwrap();
+    assert_eq!(short.to_string(), "R1,C0,M0,S2..3,B3..3,NM");
+
+    let error = |rule: &str| rule.parse::<LargerThanLife>().unwrap_err();
+    assert_eq!(error("R0,S1,B1"), RuleError::RangeOutOfRange(0));
+    assert_eq!(error("R501,S1,B1"), RuleError::RangeOutOfRange(501));
+    assert_eq!(error("R1,S2..9,B3"), RuleError::CountOutOfRange(9, 8));
+    assert_eq!(error("R1,S2..3,B3,R2"), RuleError::Duplicate('R'));
+    assert!(matches!(error("R1,S3..2,B3"), RuleError::Malformed(_)));
+}
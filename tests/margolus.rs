@@ -0,0 +1,73 @@
+//! Reversible Margolus rules must undo every step exactly, which only works
+//! where every cell is in exactly one block.
+
+mod common;
+
+use game_of_live::{Bounds, CellState, Margolus, MargolusBoard, Topology, Universe};
+
+use common::{place, soup};
+
+fn critters() -> Margolus {
+    "Critters".parse().unwrap()
+}
+
+fn cells(universe: &dyn Universe) -> Vec<CellState> {
+    let bounds = universe.bounds().unwrap();
+    (bounds.min_y..=bounds.max_y)
+        .flat_map(|y| (bounds.min_x..=bounds.max_x).map(move |x| (x, y)))
+        .map(|(x, y)| universe.get(x, y))
+        .collect()
+}
+
+#[test]
+fn critters_runs_backwards_on_torus() {
+    let bounds = Bounds::new(-50, 49, -50, 49);
+    let mut torus = MargolusBoard::new(bounds, critters())
+        .with_topology(Topology::Torus)
+        .unwrap();
+    // a fixed soup in the middle of the board
+    place(&mut torus, &soup(1, &Bounds::new(-20, 19, -20, 19)));
+    let start = cells(&torus);
+
+    for _ in 0..40 {
+        torus.step();
+    }
+    assert_ne!(cells(&torus), start);
+    assert!(torus.reverse());
+    for _ in 0..40 {
+        torus.step();
+    }
+
+    assert_eq!(cells(&torus), start);
+}
+
+#[test]
+fn odd_wrapped_axes_are_rejected() {
+    let odd = Bounds::new(-50, 50, -50, 50);
+    for &topology in &[
+        Topology::Torus,
+        Topology::Cylinder,
+        Topology::KleinBottle,
+        Topology::ProjectivePlane,
+    ] {
+        assert!(MargolusBoard::new(odd, critters())
+            .with_topology(topology)
+            .is_err());
+    }
+    // only the left and right edges are joined
+    let even_width = Bounds::new(-50, 49, -50, 50);
+    assert!(MargolusBoard::new(even_width, critters())
+        .with_topology(Topology::Cylinder)
+        .is_ok());
+}
+
+#[test]
+fn closed_edges_dont_run_backwards() {
+    let bounds = Bounds::new(-50, 50, -50, 50);
+    let mut bounded = MargolusBoard::new(bounds, critters());
+    assert!(!bounded.reverse());
+    let mut cylinder = MargolusBoard::new(Bounds::new(-50, 49, -50, 49), critters())
+        .with_topology(Topology::Cylinder)
+        .unwrap();
+    assert!(!cylinder.reverse());
+}
@@ -0,0 +1,69 @@
+//! Cells born in multi-color rules take the color of the majority of their
+//! alive neighbors, or the missing one in QuadLife, and survivors keep their
+//! color.
+
+use game_of_live::{
+    Automaton, Board, Bounds, CellState, Rule, RuleError, SparseUniverse, Universe,
+};
+
+/// Steps a blinker with the given colors from left to right once and returns
+/// the cells it turns into, from y -1 to 1.
+fn blinker(colors: [u8; 3], universe: &mut dyn Universe) -> [CellState; 3] {
+    for (x, &color) in colors.iter().enumerate() {
+        universe.set(x as i32, 0, CellState::Alive(color));
+    }
+    universe.step();
+    [universe.get(1, -1), universe.get(1, 0), universe.get(1, 1)]
+}
+
+fn rule(name: &str) -> Rule {
+    match name.parse() {
+        Ok(Automaton::Life(rule)) => rule,
+        other => panic!("{} is {:?}", name, other),
+    }
+}
+
+#[test]
+fn immigration_takes_the_majority() {
+    let immigration = rule("Immigration");
+    let mut board = Board::new(Bounds::new(-5, 5, -5, 5), immigration);
+    let mut sparse = SparseUniverse::new(immigration).unwrap();
+    for universe in [&mut board as &mut dyn Universe, &mut sparse] {
+        let [above, middle, below] = blinker([1, 0, 1], universe);
+        assert_eq!(above, CellState::Alive(1));
+        assert_eq!(below, CellState::Alive(1));
+        // the middle cell survives with its own color
+        assert_eq!(middle, CellState::Alive(0));
+    }
+}
+
+#[test]
+fn quadlife_takes_the_missing_color() {
+    let quadlife = rule("QuadLife");
+    let mut board = Board::new(Bounds::new(-5, 5, -5, 5), quadlife);
+    let [above, middle, below] = blinker([0, 1, 2], &mut board);
+    assert_eq!(above, CellState::Alive(3));
+    assert_eq!(below, CellState::Alive(3));
+    assert_eq!(middle, CellState::Alive(1));
+    assert_eq!(quadlife.birth_color(vec![2, 2, 0]), 2);
+}
+
+#[test]
+fn rules_are_written_as_parsed() {
+    for rule in &["Immigration", "QuadLife", "B36/S23/K3", "B3/S23/C3/K2"] {
+        let parsed: Automaton = rule.parse().unwrap();
+        assert_eq!(&parsed.to_string(), rule);
+    }
+    assert_eq!(rule("B3/S23/K2"), rule("immigration"));
+    assert_eq!(rule("B3/S23/K4").to_string(), "B3/S23/K4");
+    assert_eq!(rule("B3/S23/K1"), Rule::conway());
+
+    assert_eq!(
+        "B3/S23/K9".parse::<Rule>(),
+        Err(RuleError::ColorsOutOfRange(9))
+    );
+    assert_eq!(
+        "B3/S23/K0".parse::<Rule>(),
+        Err(RuleError::ColorsOutOfRange(0))
+    );
+}
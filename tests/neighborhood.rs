@@ -0,0 +1,66 @@
+//! Von Neumann and custom neighborhoods count the cells at their offsets.
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use game_of_live::{Board, Bounds, Neighborhood, Rule, RuleError, Universe};
+
+use common::{alive_cells, place};
+
+fn births_around(rule: Rule) -> BTreeSet<(i32, i32)> {
+    let area = Bounds::new(-5, 5, -5, 5);
+    let mut board = Board::new(area, rule);
+    place(&mut board, &[(0, 0)]);
+    board.step();
+    alive_cells(&board, &area)
+}
+
+#[test]
+fn von_neumann_counts_edges() {
+    let rule: Rule = "B1/SV".parse().unwrap();
+    let edges = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+    assert_eq!(births_around(rule), edges.iter().copied().collect());
+
+    for rule in &["B2/S013V", "B1/S1V"] {
+        let parsed: Rule = rule.parse().unwrap();
+        assert_eq!(&parsed.to_string(), rule);
+        assert_eq!(parsed.neighborhood(), Neighborhood::VonNeumann);
+    }
+    assert_eq!(
+        "B5/SV".parse::<Rule>(),
+        Err(RuleError::CountOutOfRange(5, 4))
+    );
+}
+
+#[test]
+fn custom_neighborhoods_count_their_offsets() {
+    let offsets = [(1, 0), (-1, 0), (0, 2)];
+    let neighborhood = Neighborhood::custom(&offsets).unwrap();
+    assert_eq!(neighborhood.size(), 3);
+    let rule = Rule::new(&[1], &[])
+        .unwrap()
+        .with_neighborhood(neighborhood)
+        .unwrap();
+    // cells that have the origin as their neighbor are born
+    let mirrored = [(-1, 0), (1, 0), (0, -2)];
+    assert_eq!(births_around(rule), mirrored.iter().copied().collect());
+    assert_eq!(
+        neighborhood.mirrored(),
+        Neighborhood::custom(&mirrored).unwrap()
+    );
+}
+
+#[test]
+fn custom_neighborhoods_are_limited() {
+    assert_eq!(
+        Neighborhood::custom(&[(4, 0)]),
+        Err(RuleError::NeighborOutOfRange(4, 0))
+    );
+    assert_eq!(
+        Rule::new(&[5], &[])
+            .unwrap()
+            .with_neighborhood(Neighborhood::VonNeumann),
+        Err(RuleError::CountOutOfRange(5, 4))
+    );
+}
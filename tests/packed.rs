@@ -0,0 +1,68 @@
+//! `PackedBoard` must run exactly like a `Board` with the same bounds and
+//! topology, including boards whose width isn't a multiple of 64 and cells
+//! next to the last bit of a word.
+
+mod common;
+
+use game_of_live::{Board, Bounds, PackedBoard, Rule, Topology};
+
+use common::{alive_cells, assert_same_cells, place, soup};
+
+const TOPOLOGIES: [Topology; 5] = [
+    Topology::Bounded,
+    Topology::Torus,
+    Topology::Cylinder,
+    Topology::KleinBottle,
+    Topology::ProjectivePlane,
+];
+
+fn start(bounds: Bounds, rule: Rule, topology: Topology) -> (Board, PackedBoard) {
+    let board = Board::new(bounds, rule).with_topology(topology);
+    let packed = PackedBoard::new(bounds, rule)
+        .unwrap()
+        .with_topology(topology);
+    (board, packed)
+}
+
+#[test]
+fn soups_follow_board() {
+    // one word, a partial second word, and a full second word plus one cell
+    let sizes = [
+        Bounds::new(0, 63, 0, 40),
+        Bounds::new(-35, 34, -20, 19),
+        Bounds::new(-64, 64, -9, 9),
+    ];
+    let rules = ["B3/S23", "B36/S23", "B1357/S1357", "B2/S"];
+    let mut seed = 0;
+    for bounds in &sizes {
+        for rule in &rules {
+            for &topology in &TOPOLOGIES {
+                seed += 1;
+                let (mut board, mut packed) = start(*bounds, rule.parse().unwrap(), topology);
+                // the soup fills the whole board, so every edge is crossed
+                let cells = soup(seed, bounds);
+                place(&mut board, &cells);
+                place(&mut packed, &cells);
+                let name = format!("{} on {:?} {:?}", rule, topology, bounds);
+                assert_same_cells(&name, &mut packed, &mut board, bounds, 16);
+            }
+        }
+    }
+}
+
+#[test]
+fn glider_returns_on_torus() {
+    let bounds = Bounds::new(0, 69, 0, 69);
+    let (mut board, mut packed) = start(bounds, Rule::conway(), Topology::Torus);
+    // right next to the end of the first word
+    let glider = [(63, 0), (64, 1), (62, 2), (63, 2), (64, 2)];
+    place(&mut board, &glider);
+    place(&mut packed, &glider);
+
+    // one cell diagonally every 4 generations, around the board once
+    assert_same_cells("glider", &mut packed, &mut board, &bounds, 4 * 70);
+    assert_eq!(
+        alive_cells(&packed, &bounds),
+        glider.iter().copied().collect()
+    );
+}
@@ -0,0 +1,52 @@
+//! Plugins are looked up by the extension of shared libraries and files
+//! that aren't plugins must fail to load instead of crashing.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use game_of_live::{plugin_paths, PluginError, PluginRule};
+
+/// Empty directory only used by this test.
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("game_of_live_{}_{}", name, process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn library(name: &str) -> String {
+    format!("{}.{}", name, std::env::consts::DLL_EXTENSION)
+}
+
+#[test]
+fn only_libraries_are_plugins() {
+    let dir = temp_dir("paths");
+    for file in &[library("b"), library("a"), "notes.txt".to_string()] {
+        fs::write(dir.join(file), b"").unwrap();
+    }
+    fs::create_dir(dir.join(library("directory"))).unwrap();
+
+    let paths = plugin_paths(&dir).unwrap();
+    assert_eq!(paths, [dir.join(library("a")), dir.join(library("b"))]);
+    assert_eq!(
+        plugin_paths(&dir.join("missing")).unwrap(),
+        Vec::<PathBuf>::new()
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn invalid_libraries_fail() {
+    let missing = Path::new("missing").join(library("plugin"));
+    // neither file is a library, so no code of them runs
+    let result = unsafe { PluginRule::load(&missing) };
+    assert!(matches!(result, Err(PluginError::Load(_))));
+
+    let dir = temp_dir("invalid");
+    let path = dir.join(library("invalid"));
+    fs::write(&path, b"not a library").unwrap();
+    let result = unsafe { PluginRule::load(&path) };
+    assert!(matches!(result, Err(PluginError::Load(_))));
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,78 @@
+//! Rules in B/S notation must be written back the way they are read and run
+//! the patterns they are known for. Their digits are counts of the Moore
+//! neighborhood, larger counts of custom neighborhoods must be separated by
+//! commas.
+
+mod common;
+
+use game_of_live::{Board, Bounds, CellState, Rule, RuleError, Universe};
+
+use common::{alive_cells, place};
+
+#[test]
+fn rules_are_written_as_parsed() {
+    for rule in &["B3/S23", "B36/S23", "B3678/S34678", "B2/S", "B/S012345678"] {
+        let parsed: Rule = rule.parse().unwrap();
+        assert_eq!(&parsed.to_string(), rule);
+    }
+    assert_eq!("b3/s23".parse::<Rule>(), Ok(Rule::conway()));
+    assert_eq!(Rule::new(&[3], &[2, 3]), Ok(Rule::conway()));
+}
+
+#[test]
+fn malformed_rules_fail() {
+    assert!(matches!("B3".parse::<Rule>(), Err(RuleError::Malformed(_))));
+    assert!(matches!("".parse::<Rule>(), Err(RuleError::Malformed(_))));
+    assert_eq!("B3/B2".parse::<Rule>(), Err(RuleError::Duplicate('B')));
+    assert_eq!(
+        "B3/S2x".parse::<Rule>(),
+        Err(RuleError::UnexpectedChar('x'))
+    );
+    assert_eq!(
+        "B3/X23".parse::<Rule>(),
+        Err(RuleError::UnexpectedChar('X'))
+    );
+}
+
+#[test]
+fn seeds_domino_splits() {
+    let area = Bounds::new(-5, 5, -5, 5);
+    let mut board = Board::new(area, "B2/S".parse().unwrap());
+    place(&mut board, &[(0, 0), (1, 0)]);
+    board.step();
+    let expected = [(0, -1), (1, -1), (0, 1), (1, 1)].iter().copied().collect();
+    assert_eq!(alive_cells(&board, &area), expected);
+    assert_eq!(board.get(0, 0), CellState::Dead);
+}
+
+#[test]
+fn digits_stop_at_eight() {
+    for rule in &["B9/S23", "B3/S239", "B99/S", "B39/S23"] {
+        assert_eq!(
+            rule.parse::<Rule>(),
+            Err(RuleError::CountOutOfRange(9, 8)),
+            "{}",
+            rule
+        );
+    }
+    let all: Rule = "B012345678/S012345678".parse().unwrap();
+    assert!((0..=8).all(|n| all.is_birth(n) && all.is_survival(n)));
+}
+
+#[test]
+fn larger_counts_need_commas() {
+    let rule: Rule = "B3,14/S2,3,49".parse().unwrap();
+    assert!(rule.is_birth(3) && rule.is_birth(14));
+    assert!(rule.is_survival(49));
+    assert_eq!(
+        "B3,50/S".parse::<Rule>(),
+        Err(RuleError::CountOutOfRange(50, 49))
+    );
+
+    for rule in &["B3,14/S2,3,13", "B9,/S23", "B3/S9,", "B2,9/S12"] {
+        let parsed: Rule = rule.parse().unwrap();
+        assert_eq!(&parsed.to_string(), rule);
+    }
+    // small counts don't need commas
+    assert_eq!("B2,3/S".parse::<Rule>(), "B23/S".parse::<Rule>());
+}
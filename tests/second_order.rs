@@ -0,0 +1,68 @@
+//! Second-order rules must run exactly backwards after `reverse`, back to
+//! the generation they started from.
+
+mod common;
+
+use game_of_live::{Automaton, Bounds, Rule, RuleError, SecondOrderBoard, Topology, Universe};
+
+use common::{alive_cells, place, soup};
+
+#[test]
+fn reverse_restores_the_start() {
+    let bounds = Bounds::new(-16, 15, -12, 11);
+    for (seed, &topology) in [Topology::Bounded, Topology::Torus, Topology::KleinBottle]
+        .iter()
+        .enumerate()
+    {
+        let mut board = SecondOrderBoard::new(bounds, Rule::conway())
+            .unwrap()
+            .with_topology(topology);
+        place(&mut board, &soup(seed as u32, &Bounds::new(-6, 5, -6, 5)));
+        let start = alive_cells(&board, &bounds);
+        for _ in 0..30 {
+            board.step();
+        }
+        assert_ne!(alive_cells(&board, &bounds), start);
+
+        assert!(board.reverse());
+        for _ in 0..30 {
+            board.step();
+        }
+        assert_eq!(alive_cells(&board, &bounds), start, "{:?}", topology);
+        assert_eq!(board.generation(), 60);
+    }
+}
+
+#[test]
+fn lone_cells_blink() {
+    // a lone cell dies, then comes back since it was alive before
+    let bounds = Bounds::new(-5, 5, -5, 5);
+    let mut board = SecondOrderBoard::new(bounds, Rule::conway()).unwrap();
+    place(&mut board, &[(0, 0)]);
+    board.step();
+    assert_eq!(board.population(), 0);
+    board.step();
+    assert_eq!(
+        alive_cells(&board, &bounds),
+        [(0, 0)].iter().copied().collect()
+    );
+}
+
+#[test]
+fn rules_are_written_as_parsed() {
+    for rule in &["B3/S23/Rev", "B36/S125/Rev"] {
+        let parsed: Automaton = rule.parse().unwrap();
+        assert!(matches!(parsed, Automaton::SecondOrder(_)));
+        assert_eq!(&parsed.to_string(), rule);
+    }
+    assert_eq!(
+        "b3/s23/rev".parse(),
+        Ok(Automaton::SecondOrder(Rule::conway()))
+    );
+
+    let generations: Rule = "B2/S/C3".parse().unwrap();
+    assert!(matches!(
+        SecondOrderBoard::new(Bounds::new(0, 9, 0, 9), generations),
+        Err(RuleError::Unsupported(_))
+    ));
+}
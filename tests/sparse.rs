@@ -0,0 +1,61 @@
+//! `SparseUniverse` must run exactly like a `Board` that is large enough to
+//! never reach its edges, and let patterns travel without bounds.
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use game_of_live::{Board, Bounds, Rule, RuleError, SparseUniverse, Universe};
+
+use common::{alive_cells, assert_same_cells, place, soup};
+
+// patterns grow by at most one cell per generation, so the soup can't reach
+// the edges in the generations that are compared
+const AREA: Bounds = Bounds {
+    min_x: -64,
+    max_x: 64,
+    min_y: -64,
+    max_y: 64,
+};
+
+#[test]
+fn soups_follow_board() {
+    let area = Bounds::new(-10, 9, -10, 9);
+    for (seed, rule) in ["B3/S23", "B36/S23", "B3678/S34678", "B2/S/C3"]
+        .iter()
+        .enumerate()
+    {
+        let rule: Rule = rule.parse().unwrap();
+        let cells = soup(seed as u32, &area);
+        let mut board = Board::new(AREA, rule);
+        let mut sparse = SparseUniverse::new(rule).unwrap();
+        place(&mut board, &cells);
+        place(&mut sparse, &cells);
+        assert_same_cells(&rule.to_string(), &mut sparse, &mut board, &AREA, 48);
+        assert_eq!(sparse.population(), board.population());
+    }
+}
+
+#[test]
+fn glider_travels_without_bounds() {
+    let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+    let mut sparse = SparseUniverse::new(Rule::conway()).unwrap();
+    place(&mut sparse, &glider);
+    // one cell diagonally every 4 generations
+    for _ in 0..400 {
+        sparse.step();
+    }
+    let moved: BTreeSet<_> = glider.iter().map(|&(x, y)| (x + 100, y + 100)).collect();
+    assert_eq!(alive_cells(&sparse, &Bounds::new(90, 110, 90, 110)), moved);
+    assert_eq!(sparse.population(), 5);
+    assert_eq!(sparse.bounds(), None);
+}
+
+#[test]
+fn birth_without_neighbors_fails() {
+    let rule: Rule = "B0/S8".parse().unwrap();
+    assert!(matches!(
+        SparseUniverse::new(rule),
+        Err(RuleError::Unsupported(_))
+    ));
+}
@@ -0,0 +1,94 @@
+//! Stochastic rules replace the outcome of the listed neighbor counts by a
+//! random one, which must be the same for the same seed.
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use game_of_live::{Board, Bounds, Rule, RuleError, Stochastic, Universe};
+
+use common::{alive_cells, place, soup};
+
+const AREA: Bounds = Bounds {
+    min_x: -15,
+    max_x: 14,
+    min_y: -15,
+    max_y: 14,
+};
+
+fn run(stochastic: &Stochastic, seed: u64) -> Board {
+    let mut board = Board::new(AREA, Rule::conway())
+        .with_stochastic(stochastic.clone(), seed)
+        .unwrap();
+    place(&mut board, &soup(3, &AREA));
+    for _ in 0..10 {
+        board.step();
+    }
+    board
+}
+
+#[test]
+fn certain_probabilities_replace_the_rule() {
+    // the ends of a blinker have a single neighbor and die anyway, the
+    // middle one never survives with 2
+    let stochastic = Stochastic {
+        survival: vec![(2, 0.0)].into_iter().collect(),
+        ..Stochastic::default()
+    };
+    let mut board = Board::new(AREA, Rule::conway())
+        .with_stochastic(stochastic, 1)
+        .unwrap();
+    place(&mut board, &[(-1, 0), (0, 0), (1, 0)]);
+    board.step();
+    let expected = [(0, -1), (0, 1)].iter().copied().collect();
+    assert_eq!(alive_cells(&board, &AREA), expected);
+
+    // probabilities of 1 for the counts of the rule change nothing
+    let stochastic = Stochastic {
+        birth: vec![(3, 1.0)].into_iter().collect(),
+        survival: vec![(2, 1.0), (3, 1.0)].into_iter().collect(),
+        noise: 0.0,
+    };
+    let random = run(&stochastic, 5);
+    let mut board = Board::new(AREA, Rule::conway());
+    place(&mut board, &soup(3, &AREA));
+    for _ in 0..10 {
+        board.step();
+    }
+    assert_eq!(alive_cells(&random, &AREA), alive_cells(&board, &AREA));
+}
+
+#[test]
+fn seeds_repeat_runs() {
+    let stochastic = Stochastic {
+        birth: vec![(3, 0.9)].into_iter().collect(),
+        survival: BTreeMap::new(),
+        noise: 0.01,
+    };
+    let first = alive_cells(&run(&stochastic, 7), &AREA);
+    assert_eq!(alive_cells(&run(&stochastic, 7), &AREA), first);
+    assert_ne!(alive_cells(&run(&stochastic, 8), &AREA), first);
+}
+
+#[test]
+fn invalid_probabilities_fail() {
+    let rule = Rule::conway();
+    let count = Stochastic {
+        birth: vec![(9, 0.5)].into_iter().collect(),
+        ..Stochastic::default()
+    };
+    assert_eq!(count.validate(&rule), Err(RuleError::CountOutOfRange(9, 8)));
+    let probability = Stochastic {
+        survival: vec![(2, 1.5)].into_iter().collect(),
+        ..Stochastic::default()
+    };
+    assert!(matches!(
+        probability.validate(&rule),
+        Err(RuleError::Unsupported(_))
+    ));
+    let noise = Stochastic {
+        noise: -0.1,
+        ..Stochastic::default()
+    };
+    assert!(Board::new(AREA, rule).with_stochastic(noise, 0).is_err());
+}
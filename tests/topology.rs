@@ -0,0 +1,167 @@
+//! Gliders on the finite surfaces must move exactly like on an infinite plane,
+//! with their cells mapped through the edges of the surface, also when a
+//! neighborhood reaches around the surface more than once.
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use game_of_live::{Board, Bounds, LargerThanLife, LtlBoard, Rule, Topology, Universe};
+
+use common::{alive_cells, assert_same_cells, place, soup};
+
+const SURFACE: Bounds = Bounds {
+    min_x: -6,
+    max_x: 5,
+    min_y: -6,
+    max_y: 5,
+};
+
+const PLANE: Bounds = Bounds {
+    min_x: -40,
+    max_x: 40,
+    min_y: -40,
+    max_y: 40,
+};
+
+/// Glider moving towards `(dx, dy)`, each either `1` or `-1`.
+fn glider(x: i32, y: i32, dx: i32, dy: i32) -> Vec<(i32, i32)> {
+    [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+        .iter()
+        .map(|&(gx, gy)| (x + (gx - 1) * dx, y + (gy - 1) * dy))
+        .collect()
+}
+
+/// Runs the glider on `topology` and on a plane large enough to never reach
+/// its edges, and checks that both agree for `generations` steps.
+fn assert_follows_plane(topology: Topology, glider: &[(i32, i32)], generations: u32) {
+    let mut surface = Board::new(SURFACE, Rule::conway()).with_topology(topology);
+    let mut plane = Board::new(PLANE, Rule::conway());
+    place(&mut surface, glider);
+    place(&mut plane, glider);
+
+    for generation in 0..=generations {
+        let expected: BTreeSet<_> = alive_cells(&plane, &PLANE)
+            .into_iter()
+            .map(|(x, y)| topology.resolve(&SURFACE, x, y).unwrap())
+            .collect();
+        assert_eq!(
+            alive_cells(&surface, &SURFACE),
+            expected,
+            "{:?} differs from the plane in generation {}",
+            topology,
+            generation
+        );
+        surface.step();
+        plane.step();
+    }
+}
+
+#[test]
+fn glider_returns_on_torus() {
+    let start = glider(0, 0, 1, 1);
+    let mut torus = Board::new(SURFACE, Rule::conway()).with_topology(Topology::Torus);
+    place(&mut torus, &start);
+
+    // moves one cell diagonally every 4 generations
+    for _ in 0..4 * SURFACE.width() {
+        torus.step();
+    }
+
+    assert_eq!(alive_cells(&torus, &SURFACE), start.into_iter().collect());
+}
+
+#[test]
+fn glider_crosses_torus_corner() {
+    assert_follows_plane(Topology::Torus, &glider(2, 2, 1, 1), 40);
+}
+
+#[test]
+fn glider_crosses_cylinder_seam() {
+    // stays away from the closed top and bottom edges
+    assert_follows_plane(Topology::Cylinder, &glider(0, -4, 1, 1), 32);
+    assert_follows_plane(Topology::Cylinder, &glider(0, 3, -1, -1), 32);
+}
+
+#[test]
+fn glider_stops_at_cylinder_edge() {
+    let mut cylinder = Board::new(SURFACE, Rule::conway()).with_topology(Topology::Cylinder);
+    place(&mut cylinder, &glider(0, 0, 1, 1));
+
+    for _ in 0..4 * SURFACE.height() {
+        cylinder.step();
+    }
+
+    // the glider turned into a block at the top edge
+    let block: BTreeSet<_> = alive_cells(&cylinder, &SURFACE);
+    assert_eq!(block.len(), 4);
+    assert!(block.iter().all(|&(_, y)| y >= SURFACE.max_y - 1));
+}
+
+#[test]
+fn glider_crosses_klein_bottle_seams() {
+    // twisted left/right edges
+    assert_follows_plane(Topology::KleinBottle, &glider(2, 0, 1, 1), 24);
+    // joined top/bottom edges
+    assert_follows_plane(Topology::KleinBottle, &glider(0, 2, -1, 1), 24);
+}
+
+#[test]
+fn glider_is_mirrored_by_klein_bottle() {
+    let start = glider(0, 0, 1, 1);
+    let mut klein = Board::new(SURFACE, Rule::conway()).with_topology(Topology::KleinBottle);
+    place(&mut klein, &start);
+
+    // one lap around the twisted direction
+    for _ in 0..4 * SURFACE.width() {
+        klein.step();
+    }
+
+    // moved down instead of up after crossing the twisted edge
+    let flip_y = |(x, y): (i32, i32)| (x, SURFACE.min_y + SURFACE.max_y - y);
+    let mirrored: BTreeSet<_> = start.into_iter().map(flip_y).collect();
+    assert_eq!(alive_cells(&klein, &SURFACE), mirrored);
+}
+
+#[test]
+fn glider_crosses_projective_plane_seams() {
+    // the corners are singular, so cross the edges in their middle
+    assert_follows_plane(Topology::ProjectivePlane, &glider(0, -5, 1, 1), 40);
+    assert_follows_plane(Topology::ProjectivePlane, &glider(-5, 0, 1, 1), 40);
+}
+
+#[test]
+fn neighborhoods_reach_around_twisted_surfaces() {
+    // reaches around the surfaces twice across their twisted edges, but not
+    // across the joined edges of the Klein bottle
+    let rule: LargerThanLife = "R7,C0,M1,S50..80,B55..70,NM".parse().unwrap();
+    // each with the torus it is folded from, made of mirrored copies of the
+    // surface next to its twisted edges
+    let surfaces = [
+        (
+            Topology::KleinBottle,
+            Bounds::new(-2, 1, -15, 14),
+            Bounds::new(-2, 5, -15, 14),
+        ),
+        (
+            Topology::ProjectivePlane,
+            Bounds::new(-3, 2, -2, 2),
+            Bounds::new(-3, 8, -2, 7),
+        ),
+    ];
+    for (seed, &(topology, area, cover)) in surfaces.iter().enumerate() {
+        let cells = soup(seed as u32, &area);
+        let mut surface = LtlBoard::new(area, rule).with_topology(topology);
+        place(&mut surface, &cells);
+        let copies: Vec<_> = (cover.min_y..=cover.max_y)
+            .flat_map(|y| (cover.min_x..=cover.max_x).map(move |x| (x, y)))
+            .filter(|&(x, y)| cells.contains(&topology.resolve(&area, x, y).unwrap()))
+            .collect();
+        let mut torus = LtlBoard::new(cover, rule).with_topology(Topology::Torus);
+        place(&mut torus, &copies);
+
+        let name = format!("{:?}", topology);
+        // the soups die out after a few generations
+        assert_same_cells(&name, &mut surface, &mut torus, &area, 6);
+    }
+}
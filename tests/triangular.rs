@@ -0,0 +1,72 @@
+//! Triangular rules count the 3 cells sharing an edge or the 12 cells
+//! sharing an edge or a corner, which depend on which way a cell points.
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use game_of_live::{Board, Bounds, Grid, Neighborhood, Rule, RuleError, Universe};
+
+use common::{alive_cells, place};
+
+fn births_around(rule: &str, x: i32, y: i32) -> BTreeSet<(i32, i32)> {
+    let area = Bounds::new(-6, 6, -6, 6);
+    let mut board = Board::new(area, rule.parse().unwrap());
+    place(&mut board, &[(x, y)]);
+    board.step();
+    alive_cells(&board, &area)
+}
+
+#[test]
+fn single_cells_give_birth_to_their_neighbors() {
+    // the cell at the origin points up and shares its base with the cell
+    // below, the one next to it points down
+    assert!(Grid::Triangular.points_up(0, 0));
+    assert!(!Grid::Triangular.points_up(1, 0));
+    let up = [(-1, 0), (1, 0), (0, -1)];
+    assert_eq!(births_around("B1/SLE", 0, 0), up.iter().copied().collect());
+    let down = [(0, 0), (2, 0), (1, 1)];
+    assert_eq!(
+        births_around("B1/SLE", 1, 0),
+        down.iter().copied().collect()
+    );
+
+    assert_eq!(births_around("B1/SL", 0, 0).len(), 12);
+    assert_eq!(births_around("B1/SL", 1, 0).len(), 12);
+}
+
+#[test]
+fn neighbors_are_symmetric() {
+    for &neighborhood in &[Neighborhood::TriangularEdges, Neighborhood::Triangular] {
+        for y in 0..2 {
+            for x in 0..2 {
+                for (dx, dy) in neighborhood.offsets(x, y) {
+                    let mut back = neighborhood.offsets(x + dx, y + dy);
+                    assert!(
+                        back.any(|offset| offset == (-dx, -dy)),
+                        "{:?} of ({}, {})",
+                        neighborhood,
+                        x,
+                        y
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn rules_are_written_as_parsed() {
+    for rule in &["B45/S34567L", "B1/S12LE", "B4a/S3bcL"] {
+        let parsed: Rule = rule.parse().unwrap();
+        assert_eq!(&parsed.to_string(), rule);
+    }
+    assert_eq!(
+        "B1/S12le".parse::<Rule>().unwrap().neighborhood(),
+        Neighborhood::TriangularEdges
+    );
+    assert_eq!(
+        "B4/SLE".parse::<Rule>(),
+        Err(RuleError::CountOutOfRange(4, 3))
+    );
+}
@@ -0,0 +1,69 @@
+//! Langton's ant and other turmites must follow their rule tables, and the
+//! tables must be written back the way they are read.
+
+use game_of_live::{
+    Bounds, CellState, Direction, RuleError, StaticBoard, Turmite, TurmiteRule, Universe,
+};
+
+#[test]
+fn ant_turns_on_colors() {
+    let rule = TurmiteRule::langtons_ant();
+    let mut board = StaticBoard::new(Bounds::new(-10, 10, -10, 10));
+    let mut ant = Turmite::new(0, 0, Direction::Up);
+    // right on every dead cell, around a 2x2 block
+    for _ in 0..4 {
+        ant.step(&rule, &mut board);
+    }
+    for &(x, y) in &[(0, 0), (1, 0), (1, -1), (0, -1)] {
+        assert_eq!(board.get(x, y), CellState::Alive(0), "({}, {})", x, y);
+    }
+    assert_eq!((ant.x, ant.y, ant.direction), (0, 0, Direction::Up));
+
+    // left on an alive cell, which dies
+    ant.step(&rule, &mut board);
+    assert_eq!(board.get(0, 0), CellState::Dead);
+    assert_eq!((ant.x, ant.y, ant.direction), (-1, 0, Direction::Left));
+    assert_eq!(board.population(), 3);
+}
+
+#[test]
+fn ant_builds_highway() {
+    let rule = TurmiteRule::langtons_ant();
+    let mut board = StaticBoard::new(Bounds::new(-100, 100, -100, 100));
+    let mut ant = Turmite::new(0, 0, Direction::Up);
+    // the highway starts after about 10000 steps and repeats every 104 steps
+    // two cells further diagonally
+    for _ in 0..11_000 {
+        ant.step(&rule, &mut board);
+    }
+    let (x, y, population) = (ant.x, ant.y, board.population());
+    for _ in 0..104 {
+        ant.step(&rule, &mut board);
+    }
+    assert_eq!(((ant.x - x).abs(), (ant.y - y).abs()), (2, 2));
+    assert_eq!(board.population(), population + 12);
+}
+
+#[test]
+fn rules_are_written_as_parsed() {
+    for rule in &[
+        "RL",
+        "RLR",
+        "LLRR",
+        "NRUL",
+        "{{{1,2,1},{1,8,1}},{{1,2,1},{0,1,0}}}",
+    ] {
+        let parsed: TurmiteRule = rule.parse().unwrap();
+        assert_eq!(&parsed.to_string(), rule);
+    }
+    // Golly's notation for an ant is written as the ant
+    let ant: TurmiteRule = "{{{1,2,0},{0,8,0}}}".parse().unwrap();
+    assert_eq!(ant, TurmiteRule::langtons_ant());
+    assert_eq!(ant.to_string(), "RL");
+    assert_eq!("rl".parse(), Ok(TurmiteRule::langtons_ant()));
+
+    assert_eq!(
+        "RX".parse::<TurmiteRule>(),
+        Err(RuleError::UnexpectedChar('X'))
+    );
+}
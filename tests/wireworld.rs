@@ -0,0 +1,61 @@
+//! Electrons must move along Wireworld wires and only pass diodes in one
+//! direction.
+
+use game_of_live::{Automaton, Bounds, CellState, Universe, WireworldBoard};
+
+/// Board with conductors where `layout` has a `#`, the first row is at y 0.
+fn circuit(layout: &[&str]) -> WireworldBoard {
+    let width = layout[0].len() as i32;
+    let height = layout.len() as i32;
+    let mut board = WireworldBoard::new(Bounds::new(0, width - 1, 0, height - 1));
+    for (y, row) in layout.iter().enumerate() {
+        for (x, c) in row.chars().enumerate() {
+            if c == '#' {
+                board.set(x as i32, y as i32, CellState::Conductor);
+            }
+        }
+    }
+    board
+}
+
+/// Sends an electron from `from` towards `to` along row `y` and returns
+/// whether it arrives.
+fn arrives(mut board: WireworldBoard, y: i32, from: i32, to: i32) -> bool {
+    let towards = (to - from).signum();
+    board.set(from, y, CellState::ElectronTail);
+    board.set(from + towards, y, CellState::ElectronHead);
+    (0..2 * board.bounds().unwrap().width()).any(|_| {
+        board.step();
+        board.get(to, y) == CellState::ElectronHead
+    })
+}
+
+#[test]
+fn electrons_move_along_wires() {
+    let mut board = circuit(&["######"]);
+    board.set(0, 0, CellState::ElectronTail);
+    board.set(1, 0, CellState::ElectronHead);
+    board.step();
+    assert_eq!(board.get(0, 0), CellState::Conductor);
+    assert_eq!(board.get(1, 0), CellState::ElectronTail);
+    assert_eq!(board.get(2, 0), CellState::ElectronHead);
+    assert_eq!(board.get(3, 0), CellState::Conductor);
+    // empty cells never change
+    assert_eq!(board.population(), 6);
+}
+
+#[test]
+fn diodes_pass_one_way() {
+    let diode = ["...##......", "####.######", "...##......"];
+    assert!(arrives(circuit(&diode), 1, 0, 10));
+    assert!(!arrives(circuit(&diode), 1, 10, 0));
+}
+
+#[test]
+fn rule_is_written_as_parsed() {
+    for rule in &["WireWorld", "wireworld", " WIREWORLD "] {
+        assert_eq!(rule.parse(), Ok(Automaton::Wireworld));
+    }
+    assert_eq!(Automaton::Wireworld.to_string(), "WireWorld");
+    assert_eq!(Automaton::Wireworld.states(), 4);
+}
@@ -0,0 +1,72 @@
+//! One-dimensional Wolfram rules must draw their known space-time diagrams,
+//! one generation per row from the top down.
+
+use game_of_live::{Automaton, Bounds, CellState, RuleError, Universe, Wolfram, WolframBoard};
+
+/// Row `y` of the board with `#` for every cell that isn't dead.
+fn row(board: &WolframBoard, y: i32) -> String {
+    let bounds = board.bounds().unwrap();
+    (bounds.min_x..=bounds.max_x)
+        .map(|x| match board.get(x, y) {
+            CellState::Dead => '.',
+            _ => '#',
+        })
+        .collect()
+}
+
+const RULE_30: [&str; 6] = [
+    ".....#.....",
+    "....###....",
+    "...##..#...",
+    "..##.####..",
+    ".##..#...#.",
+    "##.####.###",
+];
+
+#[test]
+fn rule_30_grows_from_a_single_cell() {
+    let mut board = WolframBoard::new(Bounds::new(-5, 5, 0, 5), Wolfram::elementary(30));
+    board.set(0, 5, CellState::Alive(0));
+    for _ in 1..RULE_30.len() {
+        board.step();
+    }
+    let rows: Vec<_> = (0..=5).rev().map(|y| row(&board, y)).collect();
+    assert_eq!(rows, RULE_30);
+    assert_eq!(board.population(), 9);
+
+    // the full diagram scrolls up
+    board.step();
+    assert_eq!(row(&board, 5), RULE_30[1]);
+    assert_eq!(row(&board, 1), RULE_30[5]);
+    assert_eq!(board.generation(), 6);
+}
+
+#[test]
+fn totalistic_rules_sum_colors() {
+    // code 1599 is 2012020 in base 3, the last digit is for the sum 0
+    let rule = Wolfram::totalistic(1599, 3, 1).unwrap();
+    assert_eq!(rule.next_color(&[0, 0, 0]), 0);
+    assert_eq!(rule.next_color(&[0, 1, 0]), 2);
+    assert_eq!(rule.next_color(&[1, 0, 1]), 0);
+    assert_eq!(rule.next_color(&[1, 1, 1]), 2);
+    assert_eq!(rule.next_color(&[2, 2, 0]), 1);
+    assert_eq!(Wolfram::elementary(30).next_color(&[1, 0, 0]), 1);
+    assert_eq!(Wolfram::elementary(30).next_color(&[1, 1, 0]), 0);
+}
+
+#[test]
+fn rules_are_written_as_parsed() {
+    for rule in &["W30", "W110", "T1599/K3", "T10/K2/R2"] {
+        let parsed: Wolfram = rule.parse().unwrap();
+        assert_eq!(&parsed.to_string(), rule);
+        assert_eq!(rule.parse::<Automaton>(), Ok(Automaton::Wolfram(parsed)));
+    }
+    assert_eq!("W30".parse(), Ok(Wolfram::elementary(30)));
+
+    assert!("W256".parse::<Wolfram>().is_err());
+    assert!("T1599".parse::<Wolfram>().is_err());
+    assert_eq!(
+        Wolfram::totalistic(1, 2, 4),
+        Err(RuleError::RangeOutOfRange(4))
+    );
+}